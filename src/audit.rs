@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+
+use crate::table::{qualified_name, sql_literal};
+
+/// A load log kept in a database table (`--audit-table`): one row per file loaded into
+/// a target table, written in the same transaction as the file's rows, so that the
+/// log lists a file exactly when its rows are committed.
+pub struct AuditLog {
+    /// The audit table, as given on the command line.
+    table: String,
+    /// The target table, as Exasol resolves its name.
+    target: String,
+    /// The SHA-256 of each file to load, in hex.
+    hashes: HashMap<PathBuf, String>,
+}
+
+/// A file whose content was loaded into the target before.
+pub struct Duplicate {
+    pub path: PathBuf,
+    /// When and under which name the same content was loaded.
+    pub previous: String,
+}
+
+impl AuditLog {
+    /// Creates the audit table if it does not exist yet and hashes the files of the
+    /// upload into `target`.
+    pub async fn open(
+        conn: &mut exarrow_rs::Connection,
+        table: &str,
+        target: &str,
+        paths: &[PathBuf],
+    ) -> Result<AuditLog> {
+        conn.execute_update(create_statement(table))
+            .await
+            .with_context(|| format!("failed to create audit table {table}"))?;
+
+        let mut hashes = HashMap::new();
+        for path in paths {
+            hashes.insert(path.clone(), file_hash(path)?);
+        }
+        Ok(AuditLog {
+            table: table.to_string(),
+            target: qualified_name(target),
+            hashes,
+        })
+    }
+
+    /// Finds the files of `paths` whose content the audit table records as loaded
+    /// into the target, or that repeat the content of an earlier file in `paths`.
+    pub async fn duplicates(
+        &self,
+        conn: &mut exarrow_rs::Connection,
+        paths: &[PathBuf],
+    ) -> Result<Vec<Duplicate>> {
+        let mut recorded: HashMap<String, String> = HashMap::new();
+        for batch in conn.query(self.loaded_query()).await? {
+            for row in 0..batch.num_rows() {
+                let value = |i| arrow::util::display::array_value_to_string(batch.column(i), row);
+                let (hash, file_name, loaded_at) = (value(0)?, value(1)?, value(2)?);
+                recorded
+                    .entry(hash)
+                    .or_insert(format!("as {file_name} at {loaded_at}"));
+            }
+        }
+
+        let mut duplicates = Vec::new();
+        for path in paths {
+            let hash = &self.hashes[path];
+            match recorded.get(hash) {
+                Some(previous) => duplicates.push(Duplicate {
+                    path: path.clone(),
+                    previous: previous.clone(),
+                }),
+                None => {
+                    let previous = format!("as {} earlier in this upload", path.display());
+                    recorded.insert(hash.clone(), previous);
+                }
+            }
+        }
+        Ok(duplicates)
+    }
+
+    /// Records that `rows` rows of the file at `path` were loaded. Run it in the
+    /// transaction that loads them.
+    pub async fn record(
+        &self,
+        conn: &mut exarrow_rs::Connection,
+        path: &Path,
+        rows: u64,
+    ) -> Result<()> {
+        conn.execute_update(self.insert_statement(path, rows))
+            .await
+            .with_context(|| format!("failed to record {} in {}", path.display(), self.table))?;
+        Ok(())
+    }
+
+    fn loaded_query(&self) -> String {
+        let mut hashes: Vec<String> = self.hashes.values().map(|h| sql_literal(h)).collect();
+        hashes.sort();
+        hashes.dedup();
+        format!(
+            "SELECT FILE_HASH, FILE_NAME, LOADED_AT FROM {} \
+             WHERE TARGET_TABLE = {} AND FILE_HASH IN ({}) ORDER BY LOADED_AT",
+            self.table,
+            sql_literal(&self.target),
+            hashes.join(", ")
+        )
+    }
+
+    /// The statement that records `rows` rows of the file at `path` as loaded.
+    pub fn insert_statement(&self, path: &Path, rows: u64) -> String {
+        format!(
+            "INSERT INTO {} (FILE_NAME, FILE_HASH, ROWS_LOADED, TARGET_TABLE, LOADED_AT) \
+             VALUES ({}, {}, {rows}, {}, CURRENT_TIMESTAMP)",
+            self.table,
+            sql_literal(&path.display().to_string()),
+            sql_literal(&self.hashes[path]),
+            sql_literal(&self.target)
+        )
+    }
+}
+
+fn create_statement(table: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {table} (\
+         FILE_NAME VARCHAR(2000) UTF8, \
+         FILE_HASH CHAR(64) ASCII, \
+         ROWS_LOADED DECIMAL(18,0), \
+         TARGET_TABLE VARCHAR(2000) UTF8, \
+         LOADED_AT TIMESTAMP)"
+    )
+}
+
+/// The SHA-256 of the bytes of the file at `path`, as stored, in lowercase hex.
+pub fn file_hash(path: &Path) -> Result<String> {
+    let mut file =
+        std::fs::File::open(path).with_context(|| format!("failed to open {}", path.display()))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn audit_log(paths: &[(&str, &str)]) -> AuditLog {
+        AuditLog {
+            table: "ops.load_log".into(),
+            target: qualified_name("sales.orders"),
+            hashes: paths
+                .iter()
+                .map(|(path, hash)| (PathBuf::from(path), hash.to_string()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn hash_is_sha256_of_the_file_bytes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("empty.csv");
+        std::fs::write(&path, "").unwrap();
+        assert_eq!(
+            file_hash(&path).unwrap(),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    #[test]
+    fn records_are_written_for_the_resolved_target() {
+        let audit = audit_log(&[("data/o'neil.csv", "ab12")]);
+        assert_eq!(
+            audit.insert_statement(Path::new("data/o'neil.csv"), 42),
+            "INSERT INTO ops.load_log (FILE_NAME, FILE_HASH, ROWS_LOADED, TARGET_TABLE, LOADED_AT) \
+             VALUES ('data/o''neil.csv', 'ab12', 42, 'SALES.ORDERS', CURRENT_TIMESTAMP)"
+        );
+    }
+
+    #[test]
+    fn identical_files_are_looked_up_once() {
+        let audit = audit_log(&[("a.csv", "ab12"), ("b.csv", "ab12"), ("c.csv", "cd34")]);
+        assert_eq!(
+            audit.loaded_query(),
+            "SELECT FILE_HASH, FILE_NAME, LOADED_AT FROM ops.load_log \
+             WHERE TARGET_TABLE = 'SALES.ORDERS' AND FILE_HASH IN ('ab12', 'cd34') \
+             ORDER BY LOADED_AT"
+        );
+    }
+}
@@ -6,6 +6,20 @@ use clap::{Args, Parser, Subcommand};
 pub struct Cli {
     #[command(subcommand)]
     pub command: Option<Commands>,
+
+    /// Print a machine-readable report of the run (command, rows, files, errors) on stderr when it ends
+    #[arg(long, global = true, value_enum, value_name = "FORMAT")]
+    pub report: Option<ReportFormat>,
+
+    /// Write the run report to this file instead of stderr (implies --report json)
+    #[arg(long, global = true, value_name = "PATH")]
+    pub report_file: Option<std::path::PathBuf>,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum ReportFormat {
+    /// One JSON object on a single line
+    Json,
 }
 
 #[derive(Subcommand)]
@@ -24,6 +38,20 @@ pub enum Commands {
     Bucketfs(BucketFsArgs),
 }
 
+impl Commands {
+    /// The name of the subcommand, as typed on the command line.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Commands::Upload(_) => "upload",
+            Commands::Sql(_) => "sql",
+            Commands::Export(_) => "export",
+            Commands::Interactive(_) => "interactive",
+            Commands::Profile(_) => "profile",
+            Commands::Bucketfs(_) => "bucketfs",
+        }
+    }
+}
+
 #[derive(clap::Args)]
 pub struct SqlArgs {
     /// SQL statement to execute (reads from stdin if omitted or if '-' is given)
@@ -60,10 +88,18 @@ pub enum Compression {
 
 #[derive(clap::Args)]
 pub struct UploadArgs {
-    /// Files to upload
+    /// Files, directories, or quoted glob patterns (e.g. 'landing/**/*.parquet') to upload, or '-' for stdin
     #[arg(required = true)]
     pub files: Vec<String>,
 
+    /// Include files in subdirectories when a directory is given
+    #[arg(short, long)]
+    pub recursive: bool,
+
+    /// Format of the data read from stdin ('-')
+    #[arg(long, value_enum)]
+    pub format: Option<StdinFormat>,
+
     /// Target table name (e.g., schema.table)
     #[arg(short, long)]
     pub table: String,
@@ -75,6 +111,83 @@ pub struct UploadArgs {
     #[arg(long)]
     pub dry_run: bool,
 
+    /// Override the inferred type of a column, e.g. zip=VARCHAR(10) (repeatable)
+    #[arg(long = "type", value_name = "COLUMN=TYPE")]
+    pub types: Vec<String>,
+
+    /// File with column definitions (one "column TYPE" per line) overriding inferred types
+    #[arg(long, value_name = "PATH")]
+    pub schema_file: Option<std::path::PathBuf>,
+
+    /// Import only these file columns, in this order
+    #[arg(long, value_delimiter = ',', value_name = "COLUMNS")]
+    pub columns: Vec<String>,
+
+    /// Load a file column into a differently named table column (repeatable)
+    #[arg(long, action = clap::ArgAction::Append, value_name = "FILE_COL=TABLE_COL")]
+    pub map: Vec<String>,
+
+    /// Add a column filled for every row: $FILE (the source file), $NOW (the upload start time), or a constant (repeatable)
+    #[arg(long = "add-column", action = clap::ArgAction::Append, value_name = "NAME=VALUE")]
+    pub add_columns: Vec<String>,
+
+    /// Load Hive-style key=value directories in the file paths (e.g. dt=2026-10-01/region=eu) as typed columns
+    #[arg(long)]
+    pub partition_columns: bool,
+
+    /// How file column names become table column names
+    #[arg(long, value_enum, default_value_t = ColumnNames::Quoted)]
+    pub column_names: ColumnNames,
+
+    /// How to treat the target table before loading
+    #[arg(long, value_enum, default_value_t = WriteMode::Append)]
+    pub mode: WriteMode,
+
+    /// What to do when the existing target table does not match the file columns
+    #[arg(long, value_enum, default_value_t = SchemaMismatch::Abort)]
+    pub on_schema_mismatch: SchemaMismatch,
+
+    /// Add columns that only the files have to the existing target table before loading
+    #[arg(long)]
+    pub evolve_schema: bool,
+
+    /// With --evolve-schema, also widen VARCHAR and DECIMAL columns that are too narrow for the files
+    #[arg(long, requires = "evolve_schema")]
+    pub widen_columns: bool,
+
+    /// Upsert instead of appending: update rows whose values in these columns match and insert the rest
+    #[arg(
+        long,
+        value_delimiter = ',',
+        value_name = "COLUMNS",
+        conflicts_with = "mode"
+    )]
+    pub merge_on: Vec<String>,
+
+    /// Load into a staging table first and only write to the target table once every file has loaded
+    #[arg(long)]
+    pub atomic: bool,
+
+    /// Skip files that an earlier upload into the same table recorded as loaded in its manifest
+    #[arg(long)]
+    pub resume: bool,
+
+    /// Record each loaded file (name, SHA-256, rows, target, time) in this table, in the same transaction as its rows
+    #[arg(long, value_name = "TABLE")]
+    pub audit_table: Option<String>,
+
+    /// What to do with files whose content the audit table records as loaded into the target
+    #[arg(long, value_enum, default_value_t = DuplicateFile::Abort, requires = "audit_table")]
+    pub on_duplicate_file: DuplicateFile,
+
+    /// Number of files to import concurrently, each over its own connection
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u16).range(1..))]
+    pub parallel: u16,
+
+    /// Stage each file in BucketFS (from the connection profile) and let Exasol import it from there
+    #[arg(long)]
+    pub via_bucketfs: bool,
+
     /// CSV field delimiter
     #[arg(long, default_value_t = ',')]
     pub delimiter: char,
@@ -94,6 +207,80 @@ pub struct UploadArgs {
     /// String to interpret as NULL
     #[arg(long, default_value = "")]
     pub null_value: String,
+
+    /// Skip up to N CSV rows that fail to parse or convert instead of failing the upload
+    #[arg(long, value_name = "N")]
+    pub max_errors: Option<u64>,
+
+    /// Write rejected CSV rows with their line number and reason to this file
+    #[arg(long, value_name = "PATH")]
+    pub reject_file: Option<std::path::PathBuf>,
+
+    /// Infer CSV and JSON column types from the first N rows of each file
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    pub infer_rows: Option<u64>,
+
+    /// How nested columns (JSON objects and arrays, Parquet structs, lists, and maps) become table columns
+    #[arg(long, value_enum, default_value_t = NestedColumns::Flatten)]
+    pub nested: NestedColumns,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum StdinFormat {
+    /// CSV, read with the CSV options
+    Csv,
+    /// Newline-delimited JSON, one object per line
+    Json,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum WriteMode {
+    /// Add rows to the table, creating it if it does not exist
+    Append,
+    /// Delete all existing rows before loading, creating the table if it does not exist
+    Truncate,
+    /// Drop the table and recreate it from the inferred schema
+    Replace,
+    /// Create the table and fail if it already exists
+    Create,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum SchemaMismatch {
+    /// Stop before loading if columns are missing, extra, or out of order; only warn about narrower types
+    Abort,
+    /// Stop before loading on any difference, including narrower types
+    Strict,
+    /// Report the differences and load anyway
+    Proceed,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum DuplicateFile {
+    /// Stop before loading if any file was loaded before
+    Abort,
+    /// Warn about the files loaded before and load the rest
+    Skip,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum ColumnNames {
+    /// Keep names exactly as in the file, as case-sensitive quoted identifiers
+    Quoted,
+    /// Uppercase names and replace invalid characters with underscores (my Col -> MY_COL)
+    Upper,
+    /// Split names into words at case changes and separators (customerId -> CUSTOMER_ID)
+    Snake,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum NestedColumns {
+    /// One column per nested field, named parent_child; arrays, lists, and maps are stored as JSON strings
+    Flatten,
+    /// Store nested objects, arrays, lists, and maps as JSON strings in VARCHAR columns
+    Json,
+    /// Fail before loading, listing the nested columns
+    Reject,
 }
 
 #[derive(clap::Args)]
@@ -237,3 +424,15 @@ pub enum BucketfsCommands {
         conn: BfsConnectionOverrides,
     },
 }
+
+/// Parses `exapump upload data.csv --table t` followed by `extra`, for tests of code
+/// that takes the upload arguments.
+#[cfg(test)]
+pub(crate) fn test_upload_args(extra: &[&str]) -> UploadArgs {
+    let mut argv = vec!["exapump", "upload", "data.csv", "--table", "t"];
+    argv.extend_from_slice(extra);
+    match Cli::parse_from(argv).command {
+        Some(Commands::Upload(args)) => args,
+        _ => unreachable!(),
+    }
+}
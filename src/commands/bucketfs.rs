@@ -34,6 +34,16 @@ impl BucketFsClient {
         })
     }
 
+    /// The URL of the bucket, as Exasol addresses it in `IMPORT ... AT`.
+    pub fn bucket_url(&self) -> String {
+        format!("{}/{}", self.base_url, self.bucket)
+    }
+
+    /// The password for reading from the bucket, if it is not public.
+    pub fn read_password(&self) -> Option<&str> {
+        self.read_password.as_deref()
+    }
+
     pub async fn list(&self, path: &str, recursive: bool) -> anyhow::Result<()> {
         let all_entries = self.list_bucket().await?;
         let prefix = path.trim_end_matches('/');
@@ -103,7 +113,8 @@ impl BucketFsClient {
         Ok(entries)
     }
 
-    pub async fn upload(&self, source: &str, destination: &str) -> anyhow::Result<()> {
+    /// Uploads a local file and returns its path within the bucket.
+    pub async fn upload(&self, source: &str, destination: &str) -> anyhow::Result<String> {
         let write_password = self.write_password.as_deref().ok_or_else(|| {
             anyhow::anyhow!("bfs_write_password is required for write operations")
         })?;
@@ -145,8 +156,7 @@ impl BucketFsClient {
             anyhow::bail!("BucketFS returned HTTP {status}");
         }
 
-        eprintln!("Uploaded {source} to {dest}");
-        Ok(())
+        Ok(dest)
     }
 
     pub async fn download(&self, source: &str, destination: &str) -> anyhow::Result<()> {
@@ -217,7 +227,6 @@ impl BucketFsClient {
             anyhow::bail!("BucketFS returned HTTP {status}");
         }
 
-        eprintln!("Deleted {path}");
         Ok(())
     }
 }
@@ -229,18 +238,7 @@ pub async fn run(args: BucketFsArgs) -> anyhow::Result<()> {
         BucketfsCommands::Rm { conn, .. } => conn,
     };
 
-    let config = config::load_config()?;
-    let profile = match &overrides.profile {
-        Some(name) => config
-            .get(name)
-            .ok_or_else(|| anyhow::anyhow!("Profile '{}' not found", name))?
-            .clone(),
-        None => {
-            let (_, p) = config::find_default_profile(&config)?;
-            p.clone()
-        }
-    };
-
+    let profile = load_profile(overrides.profile.as_deref())?;
     let conn = resolve_connection(&profile.resolve_bfs_connection(), overrides);
     let bfs = BucketFsClient::new(conn)?;
 
@@ -254,12 +252,38 @@ pub async fn run(args: BucketFsArgs) -> anyhow::Result<()> {
             ..
         } => {
             if Path::new(&source).exists() {
-                bfs.upload(&source, &destination).await
+                let dest = bfs.upload(&source, &destination).await?;
+                eprintln!("Uploaded {source} to {dest}");
+                Ok(())
             } else {
                 bfs.download(&source, &destination).await
             }
         }
-        BucketfsCommands::Rm { path, .. } => bfs.delete(&path).await,
+        BucketfsCommands::Rm { path, .. } => {
+            bfs.delete(&path).await?;
+            eprintln!("Deleted {path}");
+            Ok(())
+        }
+    }
+}
+
+/// Connects to the BucketFS of a connection profile, or of the default profile.
+pub fn client_for_profile(name: Option<&str>) -> anyhow::Result<BucketFsClient> {
+    let profile = load_profile(name)?;
+    BucketFsClient::new(profile.resolve_bfs_connection())
+}
+
+fn load_profile(name: Option<&str>) -> anyhow::Result<config::Profile> {
+    let config = config::load_config()?;
+    match name {
+        Some(name) => config
+            .get(name)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Profile '{}' not found", name)),
+        None => {
+            let (_, p) = config::find_default_profile(&config)?;
+            Ok(p.clone())
+        }
     }
 }
 
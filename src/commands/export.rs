@@ -10,6 +10,7 @@ use parquet::basic::Compression as ParquetCodec;
 use parquet::file::properties::WriterProperties;
 
 use crate::cli::{Compression, ExportArgs, ExportFormat};
+use crate::report::{FileReport, Report, Status};
 
 /// Build a SQL query that returns zero rows but carries the column schema
 /// for a given export source.
@@ -63,8 +64,25 @@ fn map_compression_to_codec(comp: Option<&Compression>) -> ParquetCodec {
     }
 }
 
+/// Records the rows exported and the files written in the run report. Split files
+/// are named after `base`, unless only one was written.
+fn report_output(report: &mut Report, base: &Path, rows: u64, num_files: u32) {
+    report.rows = Some(rows);
+    report.files = if num_files == 1 {
+        vec![FileReport {
+            rows: Some(rows),
+            ..FileReport::new(base, Status::Succeeded)
+        }]
+    } else {
+        (0..num_files)
+            .map(|i| FileReport::new(&crate::split::split_path(base, i), Status::Succeeded))
+            .collect()
+    };
+    report.bytes = Some(report.files.iter().filter_map(|f| f.bytes).sum());
+}
+
 /// Executes the export command: exports a table or query result to a file.
-pub async fn run(args: ExportArgs) -> anyhow::Result<()> {
+pub async fn run(args: ExportArgs, report: &mut Report) -> anyhow::Result<()> {
     if args.compression.is_some() && matches!(args.format, ExportFormat::Csv) {
         anyhow::bail!("--compression is only supported for Parquet format");
     }
@@ -81,6 +99,15 @@ pub async fn run(args: ExportArgs) -> anyhow::Result<()> {
     } else {
         anyhow::bail!("either --table or --query must be provided");
     };
+    report.source = args.table.clone().or_else(|| args.query.clone());
+    report.target = Some(args.output.clone());
+
+    // The size of the result is not known up front, so progress shows what has
+    // been written so far. CSV results are only written once exarrow has received
+    // all of them, so counting them as they are written would sit at zero and then
+    // jump to the end; CSV exports show just the elapsed time.
+    let progress = crate::progress::Progress::start(None);
+    let meter = progress.meter();
 
     match args.format {
         ExportFormat::Csv => {
@@ -116,16 +143,20 @@ pub async fn run(args: ExportArgs) -> anyhow::Result<()> {
                     .await?;
 
                 let (total_rows, num_files) = split_writer.finish()?;
+                progress.finish();
 
                 if num_files == 1 {
                     crate::split::rename_single_split(base_path)?;
                 }
 
+                report_output(report, base_path, total_rows, num_files);
                 eprintln!("Exported {total_rows} rows to {num_files} file(s)");
             } else {
                 let mut conn = args.conn.connect().await?;
                 let rows = conn.export_csv_to_file(source, base_path, options).await?;
+                progress.finish();
 
+                report_output(report, base_path, rows, 1);
                 eprintln!("Exported {rows} rows");
             }
         }
@@ -166,7 +197,9 @@ pub async fn run(args: ExportArgs) -> anyhow::Result<()> {
                 let batches = conn.export_to_record_batches(source, arrow_opts).await?;
 
                 if batches.is_empty() {
+                    progress.finish();
                     eprintln!("Exported 0 rows to 1 file(s)");
+                    report_output(report, base_path, 0, 1);
                     // Ensure the output path exists even when the query returns no data.
                     let compression = map_compression(args.compression.as_ref());
                     let options = ParquetExportOptions::new().with_compression(compression);
@@ -215,18 +248,23 @@ pub async fn run(args: ExportArgs) -> anyhow::Result<()> {
                         current_file_rows = 0;
                     }
 
+                    let written = writer.bytes_written();
                     writer.write(batch)?;
+                    meter.add_bytes((writer.bytes_written() - written) as u64);
+                    meter.add_rows(batch_rows);
                     current_file_rows += batch_rows;
                     total_rows += batch_rows;
                 }
 
                 writer.close()?;
+                progress.finish();
 
                 let num_files = file_index + 1;
                 if file_index == 0 {
                     crate::split::rename_single_split(base_path)?;
                 }
 
+                report_output(report, base_path, total_rows, num_files);
                 eprintln!("Exported {total_rows} rows to {num_files} file(s)");
             } else {
                 let compression = map_compression(args.compression.as_ref());
@@ -234,7 +272,9 @@ pub async fn run(args: ExportArgs) -> anyhow::Result<()> {
 
                 let mut conn = args.conn.connect().await?;
                 let rows = conn.export_to_parquet(source, base_path, options).await?;
+                progress.finish();
 
+                report_output(report, base_path, rows, 1);
                 eprintln!("Exported {rows} rows");
             }
         }
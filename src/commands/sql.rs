@@ -3,6 +3,7 @@ use std::io::{IsTerminal, Read, Write};
 use arrow::array::RecordBatch;
 
 use crate::cli::{OutputFormat, SqlArgs};
+use crate::report::{Report, StatementReport, Status};
 
 const STATUS_LINE_MAX_SQL_LEN: usize = 60;
 
@@ -307,8 +308,43 @@ pub fn write_json(batches: &[RecordBatch]) -> anyhow::Result<()> {
     Ok(())
 }
 
-/// Execute the `sql` subcommand.
-pub async fn run(args: SqlArgs) -> anyhow::Result<()> {
+/// What a successfully executed statement produced.
+enum StatementOutput {
+    /// The result set of a query.
+    Rows(Vec<RecordBatch>),
+    /// The number of rows changed by DML.
+    Affected(i64),
+    /// Nothing to show, as for DDL.
+    Done,
+}
+
+async fn execute_statement(
+    conn: &mut exarrow_rs::Connection,
+    stmt: &str,
+) -> Result<StatementOutput, exarrow_rs::QueryError> {
+    match StatementType::from_sql(stmt) {
+        StatementType::Query => {
+            let result_set = conn.execute(stmt).await?;
+            Ok(StatementOutput::Rows(result_set.fetch_all().await?))
+        }
+        StatementType::Dml => Ok(StatementOutput::Affected(conn.execute_update(stmt).await?)),
+        StatementType::Ddl => {
+            conn.execute_update(stmt).await?;
+            Ok(StatementOutput::Done)
+        }
+        StatementType::Execute => {
+            let result_set = conn.execute(stmt).await?;
+            if result_set.is_stream() {
+                Ok(StatementOutput::Rows(result_set.fetch_all().await?))
+            } else {
+                Ok(StatementOutput::Done)
+            }
+        }
+    }
+}
+
+/// Execute the `sql` subcommand, recording each statement in the run report.
+pub async fn run(args: SqlArgs, report: &mut Report) -> anyhow::Result<()> {
     let sql_input = resolve_sql_input(&args)?;
 
     let statements = split_statements(&sql_input);
@@ -327,111 +363,68 @@ pub async fn run(args: SqlArgs) -> anyhow::Result<()> {
     for (i, stmt) in statements.iter().enumerate() {
         let stmt_num = i + 1;
         let display_sql = truncate_sql(stmt, STATUS_LINE_MAX_SQL_LEN);
-        let stmt_type = StatementType::from_sql(stmt);
 
         eprint!("[{}/{}] {}", stmt_num, total, display_sql);
 
-        match stmt_type {
-            StatementType::Query => match conn.execute(stmt.as_str()).await {
-                Ok(result_set) => match result_set.fetch_all().await {
-                    Ok(batches) => {
-                        let row_count: usize = batches.iter().map(|b| b.num_rows()).sum();
-                        eprintln!(" {} rows", row_count);
-                        executed += 1;
-
-                        if !first_select {
-                            println!();
-                        }
-                        first_select = false;
-
-                        match args.format {
-                            OutputFormat::Csv => write_csv(&batches)?,
-                            OutputFormat::Json => write_json(&batches)?,
-                        }
-                    }
-                    Err(e) => {
-                        eprintln!();
-                        failed += 1;
-                        format_error(stmt_num, stmt, &e);
-                        exec_error = Some(e.into());
-                        break;
-                    }
-                },
-                Err(e) => {
-                    eprintln!();
-                    failed += 1;
-                    format_error(stmt_num, stmt, &e);
-                    exec_error = Some(e.into());
-                    break;
-                }
-            },
-            StatementType::Dml => match conn.execute_update(stmt.as_str()).await {
-                Ok(row_count) => {
-                    executed += 1;
-                    eprintln!(" {} rows affected", row_count);
-                }
-                Err(e) => {
-                    eprintln!();
-                    failed += 1;
-                    format_error(stmt_num, stmt, &e);
-                    exec_error = Some(e.into());
-                    break;
+        let mut statement = StatementReport {
+            sql: stmt.clone(),
+            status: Status::Succeeded,
+            rows: None,
+            error: None,
+        };
+        match execute_statement(&mut conn, stmt).await {
+            Ok(StatementOutput::Rows(batches)) => {
+                let row_count: usize = batches.iter().map(|b| b.num_rows()).sum();
+                eprintln!(" {} rows", row_count);
+                executed += 1;
+                statement.rows = Some(row_count as u64);
+
+                if !first_select {
+                    println!();
                 }
-            },
-            StatementType::Ddl => match conn.execute_update(stmt.as_str()).await {
-                Ok(_) => {
-                    executed += 1;
-                    eprintln!(" OK");
-                }
-                Err(e) => {
-                    eprintln!();
-                    failed += 1;
-                    format_error(stmt_num, stmt, &e);
-                    exec_error = Some(e.into());
-                    break;
-                }
-            },
-            StatementType::Execute => match conn.execute(stmt.as_str()).await {
-                Ok(result_set) => {
-                    if result_set.is_stream() {
-                        match result_set.fetch_all().await {
-                            Ok(batches) => {
-                                let row_count: usize = batches.iter().map(|b| b.num_rows()).sum();
-                                eprintln!(" {} rows", row_count);
-                                executed += 1;
-                                if !first_select {
-                                    println!();
-                                }
-                                first_select = false;
-                                match args.format {
-                                    OutputFormat::Csv => write_csv(&batches)?,
-                                    OutputFormat::Json => write_json(&batches)?,
-                                }
-                            }
-                            Err(e) => {
-                                eprintln!();
-                                failed += 1;
-                                format_error(stmt_num, stmt, &e);
-                                exec_error = Some(e.into());
-                                break;
-                            }
-                        }
-                    } else {
-                        executed += 1;
-                        eprintln!(" OK");
-                    }
-                }
-                Err(e) => {
-                    eprintln!();
-                    failed += 1;
-                    format_error(stmt_num, stmt, &e);
-                    exec_error = Some(e.into());
-                    break;
+                first_select = false;
+
+                match args.format {
+                    OutputFormat::Csv => write_csv(&batches)?,
+                    OutputFormat::Json => write_json(&batches)?,
                 }
-            },
+            }
+            Ok(StatementOutput::Affected(row_count)) => {
+                executed += 1;
+                statement.rows = u64::try_from(row_count).ok();
+                eprintln!(" {} rows affected", row_count);
+            }
+            Ok(StatementOutput::Done) => {
+                executed += 1;
+                eprintln!(" OK");
+            }
+            Err(e) => {
+                eprintln!();
+                failed += 1;
+                format_error(stmt_num, stmt, &e);
+                statement.status = Status::Failed;
+                statement.error = Some(e.to_string());
+                report.statements.push(statement);
+                exec_error = Some(e.into());
+                break;
+            }
         }
-    }
-
+        report.statements.push(statement);
+    }
+
+    report
+        .statements
+        .extend(
+            statements
+                .iter()
+                .skip(executed + failed)
+                .map(|stmt| StatementReport {
+                    sql: stmt.clone(),
+                    status: Status::NotStarted,
+                    rows: None,
+                    error: None,
+                }),
+        );
     print_summary(executed, failed);
 
     if let Some(e) = exec_error {
@@ -1,23 +1,1206 @@
-use crate::cli::UploadArgs;
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use anyhow::Context;
+use exarrow_rs::types::{quote_identifier, widen_type, ColumnNameMode, InferredTableSchema};
+
+use crate::audit::AuditLog;
+use crate::cli::{
+    ColumnNames, DuplicateFile, NestedColumns, SchemaMismatch, StdinFormat, UploadArgs, WriteMode,
+};
+use crate::compression::{ByteCounts, Compression};
 use crate::format::FileFormat;
+use crate::manifest::{EntryStatus, Manifest};
+use crate::progress::{Meter, Progress};
+use crate::reject::{CheckedRows, Rejects, RowCheck};
+use crate::report::{FileReport, Report, Status};
+use crate::schema_diff::ColumnDiff;
+use crate::stdin::Spooled;
+use crate::table::sql_literal;
+
+use super::bucketfs::BucketFsClient;
 
-/// Executes the upload command: validates input, then either previews schema or imports data.
-pub async fn run(args: UploadArgs) -> anyhow::Result<()> {
-    let file = &args.files[0];
-    let path = std::path::Path::new(file);
+/// The exit code of an upload that loaded the files but rejected some of their rows.
+const REJECTED_ROWS_EXIT_CODE: u8 = 3;
 
-    if !path.exists() {
-        anyhow::bail!("file not found: {}", path.display());
+/// Numbers the files staged in BucketFS by this process, to keep their paths apart.
+static STAGED_FILES: AtomicUsize = AtomicUsize::new(0);
+
+/// Executes the upload command: validates input, infers one schema across all files,
+/// then either previews it or imports every file into the target table.
+pub async fn run(args: UploadArgs, report: &mut Report) -> anyhow::Result<ExitCode> {
+    report.target = Some(args.table.clone());
+    let stdin_format = stdin_format(&args)?;
+    let (mut paths, format) = match stdin_format {
+        Some(format) => (vec![PathBuf::from(crate::stdin::STDIN_NAME)], format),
+        None => {
+            let paths = crate::expand::expand_inputs(&args.files, args.recursive)?;
+            let format = crate::format::detect_common(&paths)?;
+            (paths, format)
+        }
+    };
+    if args.partition_columns && stdin_format.is_some() {
+        anyhow::bail!(
+            "--partition-columns reads the file paths and cannot be used with stdin ('-')"
+        );
+    }
+    if args.audit_table.is_some() && stdin_format.is_some() {
+        anyhow::bail!(
+            "--audit-table cannot be used with stdin ('-'), which is not hashed before loading"
+        );
     }
+    let mut skipped = Vec::new();
+    if args.resume {
+        check_resume(&args, stdin_format.is_some())?;
+        let total = paths.len();
+        (paths, skipped) = crate::manifest::skip_loaded(
+            &crate::manifest::database(&args.conn)?,
+            &args.table,
+            paths,
+        )?;
+        if !skipped.is_empty() {
+            println!(
+                "Skipping {} of {total} files already loaded into {} (--resume)",
+                skipped.len(),
+                args.table
+            );
+        }
+        if paths.is_empty() {
+            return Ok(nothing_left(report, &skipped));
+        }
+    }
+    if crate::reject::enabled(&args) && format != FileFormat::Csv {
+        anyhow::bail!("--max-errors and --reject-file only apply to CSV input");
+    }
+    let (json_layout, parquet_layout, mut file_schema, stdin) = match stdin_format {
+        Some(_) => {
+            let (layout, schema, spooled) = infer_stdin_schema(format, &args, &paths)?;
+            (layout, None, schema, Some(spooled))
+        }
+        None => {
+            let (json_layout, parquet_layout, schema) = infer_file_schema(&paths, format, &args)?;
+            (json_layout, parquet_layout, schema, None)
+        }
+    };
+    crate::naming::apply(&mut file_schema, args.column_names);
 
-    let format = crate::format::detect_from_path(path)?;
+    let renames: Vec<_> = crate::mapping::parse_renames(&args.map)?
+        .into_iter()
+        .map(|(from, to)| {
+            let to = crate::naming::table_column_name(&to, args.column_names);
+            (from, to)
+        })
+        .collect();
+    let (mut schema, mapping) = crate::mapping::apply(&file_schema, &args.columns, &renames)?;
+    let mut added = Vec::new();
+    if args.partition_columns {
+        added = crate::partition::partition_columns(&paths)?;
+    }
+    added.extend(crate::lineage::parse_added_columns(&args.add_columns)?);
+    crate::lineage::apply(&mut schema, &added, args.column_names)?;
+    let mut type_overrides = match &args.schema_file {
+        Some(path) => crate::overrides::read_schema_file(path)?,
+        None => Vec::new(),
+    };
+    type_overrides.extend(crate::overrides::parse_type_overrides(&args.types)?);
+    crate::overrides::apply(&mut schema, &type_overrides)?;
+    let mapped = !args.columns.is_empty() || !renames.is_empty();
+    let merge_keys = resolve_merge_keys(&schema, &args.merge_on)?;
+    if args.via_bucketfs {
+        check_via_bucketfs(&args, format, &paths, parquet_layout.is_some())?;
+        if !mapping.keeps_all_columns(file_schema.columns.len()) {
+            anyhow::bail!("--via-bucketfs loads whole files and cannot be combined with --columns");
+        }
+        if !added.is_empty() {
+            anyhow::bail!(
+                "--via-bucketfs loads whole files and cannot be combined with \
+                 --add-column or --partition-columns"
+            );
+        }
+    }
 
-    match (format, args.dry_run) {
-        (FileFormat::Parquet, true) => parquet_dry_run(path, &args.table),
-        (FileFormat::Parquet, false) => parquet_import(path, &args).await,
-        (FileFormat::Csv, true) => csv_dry_run(path, &args),
-        (FileFormat::Csv, false) => csv_import(path, &args).await,
+    if args.dry_run {
+        let nested = json_layout.as_ref().map(|l| l.nested());
+        if let Some(layout) = nested.or(parquet_layout.as_ref()) {
+            print_nested(layout);
+        }
+        if mapped || args.column_names != ColumnNames::Quoted {
+            print_mapping(&mapping);
+        }
+        if format == FileFormat::Csv && stdin.is_none() {
+            print_type_evidence(&paths, &args, &file_schema, &mapping.selected)?;
+        }
+        print_schema(&schema, &args.table);
+        return Ok(ExitCode::SUCCESS);
     }
+
+    let args = Arc::new(args);
+    let mut conn = args.conn.connect().await?;
+
+    if args.mode == WriteMode::Create && crate::table::table_exists(&mut conn, &args.table).await? {
+        anyhow::bail!("table {} already exists (--mode create)", args.table);
+    }
+    let audit = match &args.audit_table {
+        Some(audit_table) => {
+            let audit = AuditLog::open(&mut conn, audit_table, &args.table, &paths).await?;
+            let duplicates = audit.duplicates(&mut conn, &paths).await?;
+            skip_duplicates(&args, duplicates, &mut paths, &mut skipped)?;
+            if paths.is_empty() {
+                return Ok(nothing_left(report, &skipped));
+            }
+            Some(Arc::new(audit))
+        }
+        None => None,
+    };
+    let lineage = crate::lineage::Lineage::start(&mut conn, added).await?;
+    let by_name = mapped || args.evolve_schema;
+    let mut target_columns = None;
+    if args.mode != WriteMode::Replace {
+        if args.evolve_schema {
+            evolve_target_schema(&mut conn, &schema, &args.table, args.widen_columns).await?;
+        }
+        check_target_schema(
+            &mut conn,
+            &schema,
+            &args.table,
+            args.on_schema_mismatch,
+            by_name || !merge_keys.is_empty(),
+        )
+        .await?;
+        if by_name {
+            target_columns = resolve_target_columns(&mut conn, &schema, &args.table).await?;
+        }
+    }
+
+    let checked = if crate::reject::enabled(&args) {
+        let check = RowCheck {
+            field_count: file_schema.columns.len(),
+            selected: mapping.selected.clone(),
+            types: schema
+                .columns
+                .iter()
+                .map(|c| c.exasol_type.clone())
+                .collect(),
+            names: mapping
+                .selected
+                .iter()
+                .map(|&i| file_schema.columns[i].original_name.clone())
+                .collect(),
+            has_header: !args.no_header,
+            null_value: args.null_value.clone(),
+        };
+        let file_columns: Vec<String> = file_schema
+            .columns
+            .iter()
+            .map(|c| c.original_name.clone())
+            .collect();
+        Some((
+            Arc::new(check),
+            Arc::new(Rejects::new(&args, &file_columns)?),
+        ))
+    } else {
+        None
+    };
+
+    // Added columns are filled client-side, so every file is read by exapump and
+    // streamed through the projection one batch at a time.
+    let projection = (!mapping.keeps_all_columns(file_schema.columns.len()) || !lineage.is_empty())
+        .then(|| (file_schema.columns.len(), mapping.selected.clone()));
+
+    let total_bytes = match stdin_format {
+        Some(_) => None,
+        None => Some(paths.iter().map(|path| file_size(path)).sum()),
+    };
+    let manifest = if stdin_format.is_none() && (paths.len() > 1 || args.resume) {
+        let database = crate::manifest::database(&args.conn)?;
+        Some(Arc::new(Manifest::create(
+            &database,
+            &args.table,
+            args.resume,
+        )?))
+    } else {
+        None
+    };
+    let progress = Progress::start(total_bytes);
+    let bucketfs = if args.via_bucketfs {
+        let client = super::bucketfs::client_for_profile(args.conn.profile.as_deref())
+            .context("--via-bucketfs reads the BucketFS settings from the connection profile")?;
+        Some(client)
+    } else {
+        None
+    };
+
+    // The worker connections are opened before a staging table is created, so that
+    // failing to open them leaves nothing behind.
+    let workers = usize::from(args.parallel).min(paths.len());
+    let mut worker_connections = Vec::new();
+    for _ in 1..workers {
+        worker_connections.push(args.conn.connect().await?);
+    }
+
+    let staged = args.atomic || !merge_keys.is_empty();
+    let plan = if staged {
+        let staging =
+            crate::table::staging_table_name(&args.table, &std::process::id().to_string());
+        let (schema_name, table_name) = super::parse_table_name(&staging);
+        conn.execute_update(schema.to_ddl(table_name, schema_name))
+            .await?;
+        ImportPlan {
+            format,
+            table: staging,
+            columns: None,
+            projection,
+            json_layout,
+            parquet_layout,
+            stdin: Mutex::new(stdin),
+            checked,
+            bucketfs,
+            manifest: None,
+            audit: None,
+            lineage,
+            progress: progress.meter(),
+        }
+    } else {
+        prepare_target(&mut conn, &schema, &args.table, args.mode).await?;
+        ImportPlan {
+            format,
+            table: args.table.clone(),
+            columns: target_columns.clone(),
+            projection,
+            json_layout,
+            parquet_layout,
+            stdin: Mutex::new(stdin),
+            checked,
+            bucketfs,
+            manifest: manifest.clone(),
+            audit: audit.clone(),
+            lineage,
+            progress: progress.meter(),
+        }
+    };
+    let plan = Arc::new(plan);
+    let load_table = plan.table.as_str();
+
+    let connections = std::iter::once(conn).chain(worker_connections).collect();
+    let imported = import_files(connections, Arc::clone(&args), &paths, &plan).await;
+    let (outcomes, mut conn) = match imported {
+        Ok(imported) => imported,
+        Err(e) => {
+            // The workers took the connections with them, so the staging table is
+            // dropped over a new one.
+            if staged {
+                match args.conn.connect().await {
+                    Ok(mut conn) => discard_staging(&mut conn, load_table).await,
+                    Err(connect_error) => eprintln!(
+                        "Warning: failed to drop staging table {load_table}: {connect_error:#}"
+                    ),
+                }
+            }
+            return Err(e);
+        }
+    };
+    progress.finish();
+    report_files(report, &paths, &outcomes, staged);
+    report.files.extend(
+        skipped
+            .iter()
+            .map(|path| FileReport::new(path, Status::Skipped)),
+    );
+    report.rejected_rows = plan.checked.as_ref().map(|(_, rejects)| rejects.count());
+
+    let mut merged = None;
+    if staged {
+        if outcomes.iter().all(|o| matches!(o, Some(Ok(_)))) {
+            let audit_rows: Vec<String> = match &audit {
+                Some(audit) => paths
+                    .iter()
+                    .zip(&outcomes)
+                    .filter_map(|(path, outcome)| match outcome {
+                        Some(Ok(load)) => Some(audit.insert_statement(path, load.rows)),
+                        _ => None,
+                    })
+                    .collect(),
+                None => Vec::new(),
+            };
+            let published = publish_staging(
+                &mut conn,
+                &schema,
+                load_table,
+                &args.table,
+                target_columns.as_deref(),
+                args.mode,
+                &merge_keys,
+                &audit_rows,
+            )
+            .await;
+            merged = match published {
+                Ok(merged) => merged,
+                Err(e) => {
+                    discard_staging(&mut conn, load_table).await;
+                    return Err(e);
+                }
+            };
+            if let Some(manifest) = &manifest {
+                for (path, outcome) in paths.iter().zip(&outcomes) {
+                    if let Some(Ok(load)) = outcome {
+                        record_file(&plan, manifest, path, load.rows, EntryStatus::Loaded);
+                    }
+                }
+            }
+        } else {
+            discard_staging(&mut conn, load_table).await;
+        }
+    }
+
+    report_outcomes(&paths, outcomes, staged)?;
+    if let Some(counts) = merged {
+        println!(
+            "Merged into {}: {} rows inserted, {} rows updated",
+            args.table, counts.inserted, counts.updated
+        );
+    }
+
+    let rejected = plan
+        .checked
+        .as_ref()
+        .map_or(0, |(_, rejects)| rejects.count());
+    if rejected == 0 {
+        return Ok(ExitCode::SUCCESS);
+    }
+    match &args.reject_file {
+        Some(path) => println!("Rejected {rejected} rows, written to {}", path.display()),
+        None => println!("Rejected {rejected} rows"),
+    }
+    Ok(ExitCode::from(REJECTED_ROWS_EXIT_CODE))
+}
+
+/// Ends an upload whose files were all skipped as loaded before.
+fn nothing_left(report: &mut Report, skipped: &[PathBuf]) -> ExitCode {
+    report.files = skipped
+        .iter()
+        .map(|path| FileReport::new(path, Status::Skipped))
+        .collect();
+    println!("Nothing left to load");
+    ExitCode::SUCCESS
+}
+
+/// How the workers import each file.
+struct ImportPlan {
+    format: FileFormat,
+    /// The table the files are loaded into: the target or a staging table.
+    table: String,
+    /// The table columns the loaded columns go to, when they are not all of the
+    /// table's columns in table order.
+    columns: Option<Vec<String>>,
+    /// The number of file columns and the indices of those to load, in load order,
+    /// when not every file column is loaded in file order.
+    projection: Option<(usize, Vec<usize>)>,
+    /// How JSON records map to columns; JSON files are read client-side.
+    json_layout: Option<crate::json::JsonLayout>,
+    /// How the columns of Parquet files with structs, lists, or maps map to table
+    /// columns; such files are read client-side.
+    parquet_layout: Option<crate::nested::NestedLayout>,
+    /// The data on stdin, taken by the worker that imports it.
+    stdin: Mutex<Option<Spooled<std::io::Stdin>>>,
+    /// How CSV rows are checked, and where the rows that fail are recorded, with
+    /// `--max-errors` or `--reject-file`.
+    checked: Option<(Arc<RowCheck>, Arc<Rejects>)>,
+    /// The bucket files are staged in, with `--via-bucketfs`.
+    bucketfs: Option<BucketFsClient>,
+    /// Where each file is recorded as soon as it is imported; only when the files
+    /// load straight into the target, so that each commits on its own.
+    manifest: Option<Arc<Manifest>>,
+    /// The audit table each file is recorded in, in the transaction that imports it;
+    /// only when the files load straight into the target.
+    audit: Option<Arc<AuditLog>>,
+    /// The columns added to every row with `--add-column`.
+    lineage: crate::lineage::Lineage,
+    /// Where the bytes read and rows loaded are counted for the progress report.
+    progress: Meter,
+}
+
+/// Brings an existing target table in line with the files: adds the columns only the
+/// files have and, with `widen`, widens string and decimal columns that are too narrow
+/// for the inferred types.
+async fn evolve_target_schema(
+    conn: &mut exarrow_rs::Connection,
+    schema: &InferredTableSchema,
+    table: &str,
+    widen: bool,
+) -> anyhow::Result<()> {
+    let Some(columns) = crate::table::table_columns(conn, table).await? else {
+        return Ok(());
+    };
+
+    for diff in crate::schema_diff::diff_columns(schema, &columns) {
+        match diff {
+            ColumnDiff::Extra {
+                name,
+                ddl_name,
+                file_type,
+            } => {
+                let ty = file_type.to_ddl_type();
+                conn.execute_update(format!("ALTER TABLE {table} ADD COLUMN {ddl_name} {ty}"))
+                    .await?;
+                eprintln!("Added column {name} {ty} to {table}");
+            }
+            ColumnDiff::Narrower {
+                name,
+                column,
+                file_type,
+                table_type,
+            } if widen => {
+                let widened = crate::table::parse_column_type(&table_type)
+                    .and_then(|t| crate::schema_diff::widened_type(&file_type, &t));
+                if let Some(widened) = widened {
+                    let ty = widened.to_ddl_type();
+                    conn.execute_update(format!(
+                        "ALTER TABLE {table} MODIFY COLUMN {} {ty}",
+                        quote_identifier(&column)
+                    ))
+                    .await?;
+                    eprintln!("Widened column {name} of {table} from {table_type} to {ty}");
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the names of the existing table's columns for the loaded columns, in load
+/// order, so that the import can address them by name; `None` if the table does not
+/// exist yet.
+async fn resolve_target_columns(
+    conn: &mut exarrow_rs::Connection,
+    schema: &InferredTableSchema,
+    table: &str,
+) -> anyhow::Result<Option<Vec<String>>> {
+    let Some(columns) = crate::table::table_columns(conn, table).await? else {
+        return Ok(None);
+    };
+    target_column_names(schema, &columns, table).map(Some)
+}
+
+/// The quoted names of the table columns the loaded columns go to, in load order.
+/// Fails naming the loaded columns the table lacks, since the import would otherwise
+/// list fewer columns than it sends.
+fn target_column_names(
+    schema: &InferredTableSchema,
+    columns: &[crate::table::TableColumn],
+    table: &str,
+) -> anyhow::Result<Vec<String>> {
+    let mut names = Vec::new();
+    let mut unmatched = Vec::new();
+    for column in &schema.columns {
+        match crate::schema_diff::find_column(columns, &column.ddl_name) {
+            Some(i) => names.push(quote_identifier(&columns[i].name)),
+            None => unmatched.push(column.original_name.as_str()),
+        }
+    }
+    if !unmatched.is_empty() {
+        anyhow::bail!(
+            "{table} has no column for {}; add the columns to the table, \
+             use --evolve-schema, or leave them out with --columns",
+            unmatched.join(", ")
+        );
+    }
+    Ok(names)
+}
+
+/// Compares the inferred schema with the target table, if it exists, and prints the
+/// differences to stderr. Fails before any data is loaded if `policy` considers them
+/// blocking. When the files are imported `by_name`, column order is not reported.
+async fn check_target_schema(
+    conn: &mut exarrow_rs::Connection,
+    schema: &InferredTableSchema,
+    table: &str,
+    policy: SchemaMismatch,
+    by_name: bool,
+) -> anyhow::Result<()> {
+    let Some(columns) = crate::table::table_columns(conn, table).await? else {
+        return Ok(());
+    };
+
+    let mut diffs = crate::schema_diff::diff_columns(schema, &columns);
+    if by_name {
+        diffs.retain(|d| !matches!(d, ColumnDiff::Moved { .. }));
+    }
+    if diffs.is_empty() {
+        return Ok(());
+    }
+
+    eprintln!("Schema differences between the files and {table}:");
+    for diff in &diffs {
+        eprintln!("  {diff}");
+    }
+
+    let blocking = diffs
+        .iter()
+        .filter(|d| match policy {
+            SchemaMismatch::Abort => d.is_incompatible(by_name),
+            SchemaMismatch::Strict => true,
+            SchemaMismatch::Proceed => false,
+        })
+        .count();
+    if blocking > 0 {
+        anyhow::bail!(
+            "{table} does not match the file columns ({blocking} blocking differences); \
+             pass --on-schema-mismatch proceed to load anyway"
+        );
+    }
+
+    Ok(())
+}
+
+/// Row counts of a merge upload.
+struct MergeCounts {
+    inserted: u64,
+    updated: u64,
+}
+
+/// Maps the `--merge-on` column names to the DDL names of the inferred columns.
+fn resolve_merge_keys(
+    schema: &InferredTableSchema,
+    keys: &[String],
+) -> anyhow::Result<Vec<String>> {
+    keys.iter()
+        .map(|key| {
+            let index = crate::mapping::find_file_column(schema, key).ok_or_else(|| {
+                let names: Vec<&str> = schema
+                    .columns
+                    .iter()
+                    .map(|c| c.original_name.as_str())
+                    .collect();
+                anyhow::anyhow!(
+                    "merge column {} not found in file columns [{}]",
+                    key.trim(),
+                    names.join(", ")
+                )
+            })?;
+            Ok(schema.columns[index].ddl_name.clone())
+        })
+        .collect()
+}
+
+/// What importing one file produced.
+struct FileLoad {
+    rows: u64,
+    /// The compressed and uncompressed sizes, for compressed files.
+    bytes: Option<ByteCounts>,
+}
+
+/// Per-file import result, indexed like the file list. `None` marks a file that was
+/// never started because an earlier file failed.
+type Outcomes = Vec<Option<anyhow::Result<FileLoad>>>;
+
+/// Imports `paths` using one worker per connection. Workers pull files from a shared
+/// queue, so a slow file does not hold up the others; after the first failure no
+/// further files are started, but files already in flight run to completion.
+///
+/// Returns the outcomes together with one of the connections for follow-up statements.
+async fn import_files(
+    connections: Vec<exarrow_rs::Connection>,
+    args: Arc<UploadArgs>,
+    paths: &[PathBuf],
+    plan: &Arc<ImportPlan>,
+) -> anyhow::Result<(Outcomes, exarrow_rs::Connection)> {
+    let queue: VecDeque<(usize, PathBuf)> = paths.iter().cloned().enumerate().collect();
+    let queue = Arc::new(Mutex::new(queue));
+    let stop = Arc::new(AtomicBool::new(false));
+    let finished = Arc::new(AtomicUsize::new(0));
+    let total = paths.len();
+
+    let mut workers = tokio::task::JoinSet::new();
+    for mut conn in connections {
+        let args = Arc::clone(&args);
+        let queue = Arc::clone(&queue);
+        let stop = Arc::clone(&stop);
+        let finished = Arc::clone(&finished);
+        let plan = Arc::clone(plan);
+
+        workers.spawn(async move {
+            let mut results = Vec::new();
+            while !stop.load(Ordering::SeqCst) {
+                let Some((index, path)) = queue.lock().unwrap().pop_front() else {
+                    break;
+                };
+
+                let result = match &plan.audit {
+                    Some(audit) => audited_import(&mut conn, &path, &plan, &args, audit).await,
+                    None => import_file(&mut conn, &path, &plan, &args).await,
+                };
+                if let Some(manifest) = &plan.manifest {
+                    match &result {
+                        Ok(load) => {
+                            record_file(&plan, manifest, &path, load.rows, EntryStatus::Loaded)
+                        }
+                        Err(_) => record_file(&plan, manifest, &path, 0, EntryStatus::Failed),
+                    }
+                }
+                let done = finished.fetch_add(1, Ordering::SeqCst) + 1;
+                match &result {
+                    Ok(load) if total > 1 => {
+                        plan.progress.message(&format!(
+                            "[{done}/{total}] {}: {} rows",
+                            path.display(),
+                            load.rows
+                        ));
+                    }
+                    Ok(_) => {}
+                    Err(_) => {
+                        stop.store(true, Ordering::SeqCst);
+                        if total > 1 {
+                            plan.progress
+                                .message(&format!("[{done}/{total}] {}: failed", path.display()));
+                        }
+                    }
+                }
+                results.push((index, result));
+            }
+            (conn, results)
+        });
+    }
+
+    let mut outcomes: Outcomes = (0..total).map(|_| None).collect();
+    let mut returned = None;
+    while let Some(joined) = workers.join_next().await {
+        let (conn, results) = joined?;
+        for (index, result) in results {
+            outcomes[index] = Some(result);
+        }
+        returned.get_or_insert(conn);
+    }
+
+    let conn = returned.ok_or_else(|| anyhow::anyhow!("no import workers were started"))?;
+    Ok((outcomes, conn))
+}
+
+/// Imports a file and records it in the audit table within one transaction, so that
+/// the audit table lists the file exactly when its rows are committed.
+async fn audited_import(
+    conn: &mut exarrow_rs::Connection,
+    path: &Path,
+    plan: &ImportPlan,
+    args: &UploadArgs,
+    audit: &AuditLog,
+) -> anyhow::Result<FileLoad> {
+    conn.begin_transaction().await?;
+
+    let result = async {
+        let load = import_file(conn, path, plan, args).await?;
+        audit.record(conn, path, load.rows).await?;
+        conn.commit().await?;
+        anyhow::Ok(load)
+    }
+    .await;
+
+    if result.is_err() {
+        let _ = conn.rollback().await;
+    }
+    result
+}
+
+async fn import_file(
+    conn: &mut exarrow_rs::Connection,
+    path: &Path,
+    plan: &ImportPlan,
+    args: &UploadArgs,
+) -> anyhow::Result<FileLoad> {
+    let stdin = plan.stdin.lock().unwrap().take();
+    if let Some(spooled) = stdin {
+        return stdin_import(conn, spooled, plan, args).await;
+    }
+    if let Some(layout) = &plan.json_layout {
+        return json_import(conn, path, plan, layout).await;
+    }
+    if plan.format == FileFormat::ArrowIpc {
+        return ipc_import(conn, path, plan).await;
+    }
+    if let Some(layout) = &plan.parquet_layout {
+        return nested_parquet_import(conn, path, plan, layout).await;
+    }
+    if let Some(bucketfs) = &plan.bucketfs {
+        return bucketfs_import(conn, path, plan, args, bucketfs).await;
+    }
+    if let Some(checked) = &plan.checked {
+        let source = crate::compression::open(path)?;
+        plan.progress.watch(source.counter());
+        let counter = source.is_compressed().then(|| source.counter());
+        return Ok(FileLoad {
+            rows: checked_import(conn, source, path, plan, args, checked).await?,
+            bytes: counter.map(|c| c.counts()),
+        });
+    }
+    if let Some((column_count, selected)) = &plan.projection {
+        return projected_import(conn, path, plan, args, *column_count, selected).await;
+    }
+
+    match plan.format {
+        FileFormat::Parquet => {
+            let rows = parquet_import(conn, path, plan).await?;
+            plan.progress.add_bytes(file_size(path));
+            plan.progress.add_rows(rows);
+            Ok(FileLoad { rows, bytes: None })
+        }
+        _ => csv_import(conn, path, plan, args).await,
+    }
+}
+
+/// Imports the records of a JSON file, read client-side into the column layout of
+/// the plan and streamed to Exasol one batch at a time.
+async fn json_import(
+    conn: &mut exarrow_rs::Connection,
+    path: &Path,
+    plan: &ImportPlan,
+    layout: &crate::json::JsonLayout,
+) -> anyhow::Result<FileLoad> {
+    let source = crate::compression::open(path)?;
+    plan.progress.watch(source.counter());
+    let counter = source.is_compressed().then(|| source.counter());
+    let selected = plan.projection.as_ref().map(|(_, s)| s.clone());
+    let rows = crate::project::CsvRows::new(layout.batches(source)?, selected)
+        .with_values(plan.lineage.values(path));
+
+    let rows = import_csv_rows(conn, plan, rows).await?;
+    Ok(FileLoad {
+        rows,
+        bytes: counter.map(|c| c.counts()),
+    })
+}
+
+/// Imports an Arrow IPC file, streaming its record batches to Exasol one at a time.
+/// Only the selected columns are sent when the plan projects the file.
+async fn ipc_import(
+    conn: &mut exarrow_rs::Connection,
+    path: &Path,
+    plan: &ImportPlan,
+) -> anyhow::Result<FileLoad> {
+    let batches = crate::ipc::IpcReader::open(path)?;
+    let selected = plan.projection.as_ref().map(|(_, s)| s.clone());
+    let rows =
+        crate::project::CsvRows::new(batches, selected).with_values(plan.lineage.values(path));
+
+    let rows = import_csv_rows(conn, plan, rows).await?;
+    plan.progress.add_bytes(file_size(path));
+    Ok(FileLoad { rows, bytes: None })
+}
+
+/// Imports a Parquet file with nested columns, read client-side into the column
+/// layout of the plan and streamed to Exasol one batch at a time.
+async fn nested_parquet_import(
+    conn: &mut exarrow_rs::Connection,
+    path: &Path,
+    plan: &ImportPlan,
+    layout: &crate::nested::NestedLayout,
+) -> anyhow::Result<FileLoad> {
+    let batches = crate::nested::parquet_batches(path, layout)?;
+    let selected = plan.projection.as_ref().map(|(_, s)| s.clone());
+    let rows =
+        crate::project::CsvRows::new(batches, selected).with_values(plan.lineage.values(path));
+
+    let rows = import_csv_rows(conn, plan, rows).await?;
+    plan.progress.add_bytes(file_size(path));
+    Ok(FileLoad { rows, bytes: None })
+}
+
+/// Stages a file in BucketFS and has Exasol import it from there, so that the cluster
+/// pulls the data instead of it being streamed over the connection. The staged file
+/// is deleted afterwards, whether or not the import succeeded.
+async fn bucketfs_import(
+    conn: &mut exarrow_rs::Connection,
+    path: &Path,
+    plan: &ImportPlan,
+    args: &UploadArgs,
+    bucketfs: &BucketFsClient,
+) -> anyhow::Result<FileLoad> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| anyhow::anyhow!("cannot stage {} in BucketFS", path.display()))?
+        .to_string_lossy();
+    let destination = format!(
+        "exapump-{}-{}/{file_name}",
+        std::process::id(),
+        STAGED_FILES.fetch_add(1, Ordering::SeqCst)
+    );
+    let staged = bucketfs
+        .upload(&path.to_string_lossy(), &destination)
+        .await
+        .with_context(|| format!("failed to stage {} in BucketFS", path.display()))?;
+    plan.progress.add_bytes(file_size(path));
+
+    let sql = bucketfs_import_sql(
+        plan.format,
+        &plan.table,
+        plan.columns.as_deref(),
+        args,
+        bucketfs,
+        &staged,
+    );
+    let result = conn.execute_update(sql).await;
+    if let Err(e) = bucketfs.delete(&staged).await {
+        plan.progress.message(&format!(
+            "Warning: failed to delete {staged} from BucketFS: {e:#}"
+        ));
+    }
+
+    let rows = u64::try_from(result?).unwrap_or_default();
+    plan.progress.add_rows(rows);
+    Ok(FileLoad { rows, bytes: None })
+}
+
+/// The `IMPORT` statement that loads a file staged in BucketFS into `table` (into
+/// `columns` if given), reading CSV with the CSV arguments.
+fn bucketfs_import_sql(
+    format: FileFormat,
+    table: &str,
+    columns: Option<&[String]>,
+    args: &UploadArgs,
+    bucketfs: &BucketFsClient,
+    staged: &str,
+) -> String {
+    let mut sql = format!("IMPORT INTO {table}");
+    if let Some(columns) = columns {
+        sql.push_str(&format!(" ({})", columns.join(", ")));
+    }
+    let file_format = match format {
+        FileFormat::Parquet => "PARQUET",
+        _ => "CSV",
+    };
+    sql.push_str(&format!(
+        " FROM {file_format} AT {}",
+        sql_literal(&bucketfs.bucket_url())
+    ));
+    if let Some(password) = bucketfs.read_password() {
+        sql.push_str(&format!(
+            " USER 'r' IDENTIFIED BY {}",
+            sql_literal(password)
+        ));
+    }
+    sql.push_str(&format!(" FILE {}", sql_literal(staged)));
+
+    if format == FileFormat::Csv {
+        sql.push_str(&format!(
+            " COLUMN SEPARATOR = {} COLUMN DELIMITER = {} SKIP = {}",
+            sql_literal(&args.delimiter.to_string()),
+            sql_literal(&args.quote.to_string()),
+            header_lines(args)
+        ));
+        if !args.null_value.is_empty() {
+            sql.push_str(&format!(" NULL = {}", sql_literal(&args.null_value)));
+        }
+    }
+    sql
+}
+
+/// Imports the data on stdin, the spooled sample followed by the rest of the stream,
+/// sending it to Exasol as it is read.
+async fn stdin_import(
+    conn: &mut exarrow_rs::Connection,
+    spooled: Spooled<std::io::Stdin>,
+    plan: &ImportPlan,
+    args: &UploadArgs,
+) -> anyhow::Result<FileLoad> {
+    let source = plan.progress.bytes(spooled.into_reader());
+    let selected = plan.projection.as_ref().map(|(_, s)| s.clone());
+    let path = Path::new(crate::stdin::STDIN_NAME);
+    if let Some(checked) = &plan.checked {
+        let rows = checked_import(conn, source, path, plan, args, checked).await?;
+        return Ok(FileLoad { rows, bytes: None });
+    }
+
+    let rows = match (&plan.json_layout, &plan.projection) {
+        (Some(layout), _) => {
+            let rows = crate::project::CsvRows::new(layout.batches(source)?, selected)
+                .with_values(plan.lineage.values(path));
+            import_csv_rows(conn, plan, rows).await?
+        }
+        (None, Some((column_count, selected))) => {
+            let batches =
+                crate::project::csv_column_batches(source, args, *column_count, selected)?;
+            let rows =
+                crate::project::CsvRows::new(batches, None).with_values(plan.lineage.values(path));
+            import_csv_rows(conn, plan, rows).await?
+        }
+        (None, None) => {
+            let source = plan.progress.rows(source, header_lines(args));
+            conn.import_csv_from_stream(
+                &plan.table,
+                crate::compression::stream(source),
+                csv_import_options(plan, args),
+            )
+            .await?
+        }
+    };
+    Ok(FileLoad { rows, bytes: None })
+}
+
+/// Streams CSV rows encoded from record batches, which have no header, into the
+/// table of the plan.
+async fn import_csv_rows(
+    conn: &mut exarrow_rs::Connection,
+    plan: &ImportPlan,
+    rows: impl std::io::Read + Send + 'static,
+) -> anyhow::Result<u64> {
+    let mut options = exarrow_rs::CsvImportOptions::new();
+    if let Some(columns) = &plan.columns {
+        options = options.columns(columns.clone());
+    }
+
+    let rows = plan.progress.rows(rows, 0);
+    Ok(conn
+        .import_csv_from_stream(&plan.table, crate::compression::stream(rows), options)
+        .await?)
+}
+
+/// Imports CSV data whose rows are checked client-side first: rows that cannot be
+/// loaded are rejected instead of failing the import, until there are more than
+/// `--max-errors` of them.
+async fn checked_import(
+    conn: &mut exarrow_rs::Connection,
+    source: impl std::io::Read + Send + 'static,
+    path: &Path,
+    plan: &ImportPlan,
+    args: &UploadArgs,
+    (check, rejects): &(Arc<RowCheck>, Arc<Rejects>),
+) -> anyhow::Result<u64> {
+    let rows = CheckedRows::new(source, path, args, Arc::clone(check), Arc::clone(rejects))
+        .with_values(plan.lineage.values(path));
+    match import_csv_rows(conn, plan, rows).await {
+        Ok(rows) => Ok(rows),
+        Err(e) => {
+            // Reading stops at the limit, which fails the import with a less telling error.
+            rejects.check_limit()?;
+            Err(e)
+        }
+    }
+}
+
+/// Imports only the selected columns of a file, read client-side and streamed to
+/// Exasol one batch at a time.
+async fn projected_import(
+    conn: &mut exarrow_rs::Connection,
+    path: &Path,
+    plan: &ImportPlan,
+    args: &UploadArgs,
+    column_count: usize,
+    selected: &[usize],
+) -> anyhow::Result<FileLoad> {
+    let values = plan.lineage.values(path);
+    match plan.format {
+        FileFormat::Parquet => {
+            let batches = crate::project::parquet_column_batches(path, selected)?;
+            let rows = crate::project::CsvRows::new(batches, None).with_values(values);
+            let rows = import_csv_rows(conn, plan, rows).await?;
+            plan.progress.add_bytes(file_size(path));
+            Ok(FileLoad { rows, bytes: None })
+        }
+        _ => {
+            let source = crate::compression::open(path)?;
+            plan.progress.watch(source.counter());
+            let counter = source.is_compressed().then(|| source.counter());
+            let batches = crate::project::csv_column_batches(source, args, column_count, selected)?;
+            let rows = crate::project::CsvRows::new(batches, None).with_values(values);
+            Ok(FileLoad {
+                rows: import_csv_rows(conn, plan, rows).await?,
+                bytes: counter.map(|c| c.counts()),
+            })
+        }
+    }
+}
+
+/// Moves the staged rows into the target table within a single transaction: the
+/// target is prepared according to `mode`, filled from the staging table (into
+/// `columns` if given, merged on `merge_keys` if any are given), the `audit_rows`
+/// inserted, and the staging table dropped. On any error the
+/// transaction is rolled back, which also undoes the preparation of the target; the
+/// caller then drops the staging table.
+#[allow(clippy::too_many_arguments)]
+async fn publish_staging(
+    conn: &mut exarrow_rs::Connection,
+    schema: &InferredTableSchema,
+    staging: &str,
+    table: &str,
+    columns: Option<&[String]>,
+    mode: WriteMode,
+    merge_keys: &[String],
+    audit_rows: &[String],
+) -> anyhow::Result<Option<MergeCounts>> {
+    conn.begin_transaction().await?;
+
+    let result = async {
+        prepare_target(conn, schema, table, mode).await?;
+        let merged = if merge_keys.is_empty() {
+            let column_list = columns
+                .map(|c| format!(" ({})", c.join(", ")))
+                .unwrap_or_default();
+            conn.execute_update(format!(
+                "INSERT INTO {table}{column_list} SELECT * FROM {staging}"
+            ))
+            .await?;
+            None
+        } else {
+            Some(merge_rows(conn, schema, staging, table, merge_keys).await?)
+        };
+        for statement in audit_rows {
+            conn.execute_update(statement.as_str()).await?;
+        }
+        conn.execute_update(format!("DROP TABLE {staging}")).await?;
+        conn.commit().await?;
+        anyhow::Ok(merged)
+    }
+    .await;
+
+    match result {
+        Ok(merged) => Ok(merged),
+        Err(e) => {
+            let _ = conn.rollback().await;
+            Err(e.context(format!("failed to publish staged rows into {table}")))
+        }
+    }
+}
+
+/// Merges the staged rows into `table`, counting beforehand how many of them match
+/// an existing row and will therefore update it.
+async fn merge_rows(
+    conn: &mut exarrow_rs::Connection,
+    schema: &InferredTableSchema,
+    staging: &str,
+    table: &str,
+    keys: &[String],
+) -> anyhow::Result<MergeCounts> {
+    let columns: Vec<String> = schema.columns.iter().map(|c| c.ddl_name.clone()).collect();
+
+    let updated =
+        crate::table::query_count(conn, crate::table::matched_rows_query(table, staging, keys))
+            .await?;
+    let affected = conn
+        .execute_update(crate::table::merge_statement(
+            table, staging, &columns, keys,
+        ))
+        .await?;
+
+    Ok(MergeCounts {
+        inserted: u64::try_from(affected)?.saturating_sub(updated),
+        updated,
+    })
+}
+
+/// Drops the staging table of a staged upload that failed or was abandoned, so that it
+/// does not stay behind in the user's schema. A failure to drop it only warns.
+async fn discard_staging(conn: &mut exarrow_rs::Connection, staging: &str) {
+    if let Err(e) = drop_table(conn, staging).await {
+        eprintln!("Warning: failed to drop staging table {staging}: {e:#}");
+    }
+}
+
+/// Drops `table` and commits explicitly, since the connection may have left
+/// autocommit mode during [`publish_staging`].
+async fn drop_table(conn: &mut exarrow_rs::Connection, table: &str) -> anyhow::Result<()> {
+    conn.execute_update(format!("DROP TABLE IF EXISTS {table}"))
+        .await?;
+    conn.execute_update("COMMIT").await?;
+    Ok(())
+}
+
+/// Records what happened to each file in the run report, with the rows and file bytes
+/// that were loaded. A staged upload with a failed file loads nothing.
+fn report_files(report: &mut Report, paths: &[PathBuf], outcomes: &Outcomes, staged: bool) {
+    report.files = paths
+        .iter()
+        .zip(outcomes)
+        .map(|(path, outcome)| match outcome {
+            Some(Ok(load)) => FileReport {
+                rows: Some(load.rows),
+                ..FileReport::new(path, Status::Succeeded)
+            },
+            Some(Err(e)) => FileReport {
+                error: Some(format!("{e:#}")),
+                ..FileReport::new(path, Status::Failed)
+            },
+            None => FileReport::new(path, Status::NotStarted),
+        })
+        .collect();
+
+    let loaded = || {
+        report
+            .files
+            .iter()
+            .filter(|f| f.status == Status::Succeeded)
+    };
+    let rolled_back = staged && loaded().count() < paths.len();
+    let (rows, bytes) = if rolled_back {
+        (0, 0)
+    } else {
+        (
+            loaded().filter_map(|f| f.rows).sum(),
+            loaded().filter_map(|f| f.bytes).sum(),
+        )
+    };
+    report.rows = Some(rows);
+    report.bytes = Some(bytes);
+}
+
+/// Prints the summary of a fully successful upload, or a failure report naming every
+/// file that did not load, and turns any failure into the command's error.
+fn report_outcomes(paths: &[PathBuf], outcomes: Outcomes, staged: bool) -> anyhow::Result<()> {
+    let mut loaded = Vec::new();
+    let mut failed = Vec::new();
+    let mut skipped = Vec::new();
+
+    for (path, outcome) in paths.iter().zip(outcomes) {
+        match outcome {
+            Some(Ok(load)) => loaded.push((path.as_path(), load)),
+            Some(Err(e)) => failed.push((path.as_path(), e)),
+            None => skipped.push(path.as_path()),
+        }
+    }
+
+    if failed.is_empty() {
+        print_summary(&loaded);
+        return Ok(());
+    }
+
+    if paths.len() == 1 {
+        let (path, e) = failed.remove(0);
+        return Err(e.context(format!("failed to import {}", path.display())));
+    }
+
+    eprintln!();
+    eprintln!(
+        "Failed to import {} of {} files:",
+        failed.len(),
+        paths.len()
+    );
+    for (path, e) in &failed {
+        eprintln!("  {}: {e:#}", path.display());
+    }
+    if !skipped.is_empty() {
+        eprintln!("Not started after the failure ({} files):", skipped.len());
+        for path in &skipped {
+            eprintln!("  {}", path.display());
+        }
+    }
+    if staged {
+        eprintln!("Upload rolled back: no rows were written to the target table");
+    } else {
+        let loaded_rows: u64 = loaded.iter().map(|(_, load)| load.rows).sum();
+        eprintln!(
+            "Loaded {loaded_rows} rows from {} of {} files",
+            loaded.len(),
+            paths.len()
+        );
+    }
+
+    anyhow::bail!(
+        "{} of {} files did not load",
+        failed.len() + skipped.len(),
+        paths.len()
+    )
 }
 
 fn build_csv_inference_options(args: &UploadArgs) -> exarrow_rs::types::CsvInferenceOptions {
@@ -27,74 +1210,405 @@ fn build_csv_inference_options(args: &UploadArgs) -> exarrow_rs::types::CsvInfer
         .with_quote(Some(args.quote as u8))
         .with_escape(args.escape.map(|c| c as u8))
         .with_null_regex(Some(format!("^{}$", regex::escape(&args.null_value))))
-        .with_column_name_mode(exarrow_rs::types::ColumnNameMode::Quoted)
+        .with_max_sample_records(inference_rows(args))
+        .with_column_name_mode(ColumnNameMode::Quoted)
 }
 
-fn print_schema(schema: &exarrow_rs::types::InferredTableSchema, table: &str) {
-    println!("Columns:");
-    for col in &schema.columns {
-        println!("  {}: {}", col.ddl_name, col.exasol_type.to_ddl_type());
-    }
+/// The number of rows of each CSV file to infer column types from; `None` for all.
+fn inference_rows(args: &UploadArgs) -> Option<usize> {
+    args.infer_rows
+        .map(|rows| usize::try_from(rows).unwrap_or(usize::MAX))
+}
 
-    let (schema_name, table_name) = super::parse_table_name(table);
-    println!();
-    println!("{}", schema.to_ddl(table_name, schema_name));
+/// Records a file in the manifest. The file has already been loaded at this point, so
+/// a failure to record it only warns.
+fn record_file(
+    plan: &ImportPlan,
+    manifest: &Manifest,
+    path: &Path,
+    rows: u64,
+    status: EntryStatus,
+) {
+    if let Err(e) = manifest.record(path, rows, status) {
+        plan.progress.message(&format!(
+            "Warning: failed to record {} in the upload manifest: {e:#}",
+            path.display()
+        ));
+    }
 }
 
-fn parquet_dry_run(path: &std::path::Path, table: &str) -> anyhow::Result<()> {
-    let schema = exarrow_rs::types::infer_schema_from_parquet(
-        path,
-        exarrow_rs::types::ColumnNameMode::Quoted,
-    )?;
+/// Handles the files the audit table records as loaded into the target: fails the
+/// upload with `--on-duplicate-file abort`, and moves them from `paths` to `skipped`
+/// with `--on-duplicate-file skip`.
+fn skip_duplicates(
+    args: &UploadArgs,
+    duplicates: Vec<crate::audit::Duplicate>,
+    paths: &mut Vec<PathBuf>,
+    skipped: &mut Vec<PathBuf>,
+) -> anyhow::Result<()> {
+    if duplicates.is_empty() {
+        return Ok(());
+    }
+    let audit_table = args.audit_table.as_deref().unwrap_or_default();
+    match args.on_duplicate_file {
+        DuplicateFile::Abort => {
+            eprintln!("Files already loaded into {} ({audit_table}):", args.table);
+            for duplicate in &duplicates {
+                eprintln!("  {}: {}", duplicate.path.display(), duplicate.previous);
+            }
+            anyhow::bail!(
+                "{} files were already loaded into {}; pass --on-duplicate-file skip to load the rest",
+                duplicates.len(),
+                args.table
+            );
+        }
+        DuplicateFile::Skip => {
+            for duplicate in duplicates {
+                eprintln!(
+                    "Warning: skipping {}, already loaded into {} {}",
+                    duplicate.path.display(),
+                    args.table,
+                    duplicate.previous
+                );
+                paths.retain(|path| *path != duplicate.path);
+                skipped.push(duplicate.path);
+            }
+        }
+    }
+    Ok(())
+}
 
-    print_schema(&schema, table);
+/// Checks that `--resume` can tell which files are loaded: they are files, and the
+/// rows loaded before are kept.
+fn check_resume(args: &UploadArgs, from_stdin: bool) -> anyhow::Result<()> {
+    if from_stdin {
+        anyhow::bail!("--resume cannot be used with stdin ('-')");
+    }
+    if matches!(args.mode, WriteMode::Truncate | WriteMode::Replace) {
+        anyhow::bail!(
+            "--resume cannot be combined with --mode truncate or --mode replace, \
+             which remove the rows loaded before"
+        );
+    }
+    Ok(())
+}
 
+/// Checks that the input can be staged in BucketFS: whole CSV or Parquet files that
+/// Exasol can read as they are.
+fn check_via_bucketfs(
+    args: &UploadArgs,
+    format: FileFormat,
+    paths: &[PathBuf],
+    nested: bool,
+) -> anyhow::Result<()> {
+    if args.files.iter().any(|f| f == crate::stdin::STDIN) {
+        anyhow::bail!("--via-bucketfs cannot be used with stdin ('-')");
+    }
+    if !matches!(format, FileFormat::Csv | FileFormat::Parquet) {
+        anyhow::bail!("--via-bucketfs only applies to CSV and Parquet files");
+    }
+    if nested {
+        anyhow::bail!(
+            "--via-bucketfs cannot load Parquet files with nested columns, \
+             which are converted client-side"
+        );
+    }
+    if crate::reject::enabled(args) {
+        anyhow::bail!("--via-bucketfs cannot be combined with --max-errors or --reject-file");
+    }
+    // Exasol decompresses gzip and bzip2 files itself, but not zstd or xz.
+    if let Some(path) = paths.iter().find(|path| {
+        matches!(
+            Compression::from_path(path),
+            Some(Compression::Zstd | Compression::Xz)
+        )
+    }) {
+        anyhow::bail!(
+            "--via-bucketfs cannot load {}: Exasol only reads gzip and bzip2 compressed files",
+            path.display()
+        );
+    }
     Ok(())
 }
 
-fn csv_dry_run(path: &std::path::Path, args: &UploadArgs) -> anyhow::Result<()> {
-    let options = build_csv_inference_options(args);
-    let schema = exarrow_rs::types::infer_schema_from_csv(path, &options)?;
+/// Reads the input format from `--format` when the input is stdin ('-'), which
+/// cannot be combined with other inputs.
+fn stdin_format(args: &UploadArgs) -> anyhow::Result<Option<FileFormat>> {
+    let from_stdin = args.files.iter().any(|f| f == crate::stdin::STDIN);
+    match (from_stdin, args.format) {
+        (false, None) => Ok(None),
+        (false, Some(_)) => anyhow::bail!("--format only applies to stdin input ('-')"),
+        (true, _) if args.files.len() > 1 => {
+            anyhow::bail!("stdin ('-') cannot be combined with other inputs")
+        }
+        (true, None) => {
+            anyhow::bail!("reading from stdin ('-') requires --format csv or --format json")
+        }
+        (true, Some(StdinFormat::Csv)) => Ok(Some(FileFormat::Csv)),
+        (true, Some(StdinFormat::Json)) => Ok(Some(FileFormat::Json)),
+    }
+}
 
-    print_schema(&schema, &args.table);
+/// Infers one schema across all files, and for JSON files and Parquet files with
+/// nested columns how their fields map to columns.
+fn infer_file_schema(
+    paths: &[PathBuf],
+    format: FileFormat,
+    args: &UploadArgs,
+) -> anyhow::Result<(
+    Option<crate::json::JsonLayout>,
+    Option<crate::nested::NestedLayout>,
+    InferredTableSchema,
+)> {
+    Ok(match format {
+        FileFormat::Json => {
+            let layout = crate::json::JsonLayout::infer(paths, inference_rows(args), args.nested)?;
+            let schema = layout.table_schema(paths);
+            (Some(layout), None, schema)
+        }
+        FileFormat::Parquet => {
+            let (layout, schema) = infer_parquet_schema(paths, args.nested)?;
+            (None, layout, schema)
+        }
+        FileFormat::ArrowIpc => (None, None, infer_ipc_schema(paths)?),
+        FileFormat::Csv => (None, None, infer_csv_schema(paths, args)?),
+    })
+}
 
-    Ok(())
+/// Infers the schema of the data on stdin from its first rows (`--infer-rows`, or a
+/// default sample). The bytes read for that are spooled, so that the import replays
+/// them ahead of the rest of the stream.
+fn infer_stdin_schema(
+    format: FileFormat,
+    args: &UploadArgs,
+    paths: &[PathBuf],
+) -> anyhow::Result<(
+    Option<crate::json::JsonLayout>,
+    InferredTableSchema,
+    Spooled<std::io::Stdin>,
+)> {
+    let rows = inference_rows(args).unwrap_or(crate::stdin::DEFAULT_SAMPLE_ROWS);
+
+    if format == FileFormat::Json {
+        let (layout, spooled) = Spooled::inspect(std::io::stdin(), |reader| {
+            let sources = vec![std::io::BufReader::new(reader)];
+            crate::json::JsonLayout::infer_from(sources, Some(rows), args.nested)
+        })?;
+        let schema = layout.table_schema(paths);
+        return Ok((Some(layout), schema, spooled));
+    }
+
+    let options = build_csv_inference_options(args).with_max_sample_records(Some(rows));
+    let (schema, spooled) = Spooled::inspect(std::io::stdin(), |reader| {
+        if crate::reject::enabled(args) {
+            let rows = crate::reject::WellFormedRows::new(reader, args);
+            crate::inference::infer_csv_schema_from(rows, &paths[0], &options)
+        } else {
+            crate::inference::infer_csv_schema_from(reader, &paths[0], &options)
+        }
+    })?;
+    Ok((None, schema, spooled))
 }
 
-async fn parquet_import(path: &std::path::Path, args: &UploadArgs) -> anyhow::Result<()> {
-    let schema = exarrow_rs::types::infer_schema_from_parquet(
-        path,
-        exarrow_rs::types::ColumnNameMode::Quoted,
-    )?;
+/// Infers the schema of Parquet files from their footers. When any file has struct,
+/// list, or map columns, they are laid out as table columns following `nested`, and
+/// the layout of the first file is returned with the schema.
+fn infer_parquet_schema(
+    paths: &[PathBuf],
+    nested: NestedColumns,
+) -> anyhow::Result<(Option<crate::nested::NestedLayout>, InferredTableSchema)> {
+    let fields = paths
+        .iter()
+        .map(|path| crate::nested::parquet_fields(path))
+        .collect::<anyhow::Result<Vec<_>>>()?;
 
-    let mut conn = args.conn.connect().await?;
+    if !fields.iter().any(crate::nested::has_nested) {
+        let schemas = paths
+            .iter()
+            .map(|path| exarrow_rs::types::infer_schema_from_parquet(path, ColumnNameMode::Quoted))
+            .collect::<Result<Vec<_>, _>>()?;
+        return Ok((None, merge_schemas(schemas)?));
+    }
 
-    let (schema_name, table_name) = super::parse_table_name(&args.table);
-    let ddl = schema.to_ddl(table_name, schema_name).replacen(
-        "CREATE TABLE",
-        "CREATE TABLE IF NOT EXISTS",
-        1,
-    );
-    conn.execute(&ddl).await?;
+    let layouts = fields
+        .iter()
+        .zip(paths)
+        .map(|(fields, path)| {
+            crate::nested::NestedLayout::new(fields, nested)
+                .with_context(|| format!("cannot load {}", path.display()))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let schemas = layouts
+        .iter()
+        .zip(paths)
+        .map(|(layout, path)| layout.table_schema(std::slice::from_ref(path)))
+        .collect();
+    let schema = merge_schemas(schemas)?;
+    Ok((layouts.into_iter().next(), schema))
+}
 
-    let options = exarrow_rs::ParquetImportOptions::new()
-        .with_column_name_mode(exarrow_rs::types::ColumnNameMode::Quoted)
-        .with_native_parquet(Some(false));
+fn infer_ipc_schema(paths: &[PathBuf]) -> anyhow::Result<InferredTableSchema> {
+    let schemas = paths
+        .iter()
+        .map(|path| crate::ipc::infer_ipc_schema(path))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    merge_schemas(schemas)
+}
+
+fn infer_csv_schema(paths: &[PathBuf], args: &UploadArgs) -> anyhow::Result<InferredTableSchema> {
+    let options = build_csv_inference_options(args);
+    let schemas = paths
+        .iter()
+        .map(|path| {
+            if crate::reject::enabled(args) {
+                let rows =
+                    crate::reject::WellFormedRows::new(crate::compression::open(path)?, args);
+                crate::inference::infer_csv_schema_from(rows, path, &options)
+            } else {
+                crate::inference::infer_csv_schema(path, &options)
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    merge_schemas(schemas)
+}
+
+/// Merges per-file schemas into the single schema used for the target table.
+///
+/// Every file must carry the same column names in the same order; column types are
+/// widened so that the resulting table can hold the data of all files.
+fn merge_schemas(schemas: Vec<InferredTableSchema>) -> anyhow::Result<InferredTableSchema> {
+    let mut schemas = schemas.into_iter();
+    let mut merged = schemas
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("no files to upload"))?;
+
+    for schema in schemas {
+        let expected: Vec<&str> = merged
+            .columns
+            .iter()
+            .map(|c| c.original_name.as_str())
+            .collect();
+        let actual: Vec<&str> = schema
+            .columns
+            .iter()
+            .map(|c| c.original_name.as_str())
+            .collect();
+
+        if expected != actual {
+            anyhow::bail!(
+                "schema mismatch: {} has columns [{}], but {} has columns [{}]",
+                merged.source_files[0].display(),
+                expected.join(", "),
+                schema.source_files[0].display(),
+                actual.join(", ")
+            );
+        }
+
+        for (col, other) in merged.columns.iter_mut().zip(&schema.columns) {
+            col.exasol_type = widen_type(&col.exasol_type, &other.exasol_type);
+            col.nullable = col.nullable || other.nullable;
+        }
+        merged.source_files.extend(schema.source_files);
+    }
+
+    Ok(merged)
+}
+
+/// Prints how each nested column of the files is loaded.
+fn print_nested(layout: &crate::nested::NestedLayout) {
+    if layout.description().is_empty() {
+        return;
+    }
+    println!("Nested columns:");
+    for line in layout.description() {
+        println!("  {line}");
+    }
+    println!();
+}
 
-    let rows = conn.import_from_parquet(&args.table, path, options).await?;
+fn print_mapping(mapping: &crate::mapping::ColumnMapping) {
+    println!("Column mapping:");
+    for (file_column, table_column) in &mapping.pairs {
+        println!("  {file_column} -> {table_column}");
+    }
+    println!();
+}
 
-    println!("Imported {rows} rows");
+/// Prints, for each loaded column, the file row that decided its inferred type.
+fn print_type_evidence(
+    paths: &[PathBuf],
+    args: &UploadArgs,
+    schema: &InferredTableSchema,
+    selected: &[usize],
+) -> anyhow::Result<()> {
+    let rows = inference_rows(args);
+    let columns: Vec<String> = schema
+        .columns
+        .iter()
+        .map(|c| c.original_name.clone())
+        .collect();
+    let evidence = crate::inference::csv_type_evidence(paths, args, &columns, rows)?;
 
+    match rows {
+        Some(rows) => println!("Type inference (first {rows} rows of each file):"),
+        None => println!("Type inference (all rows):"),
+    }
+    for &i in selected {
+        println!("  {}", evidence[i]);
+    }
+    println!();
     Ok(())
 }
 
-async fn csv_import(path: &std::path::Path, args: &UploadArgs) -> anyhow::Result<()> {
-    let inference_options = build_csv_inference_options(args);
-    let schema = exarrow_rs::types::infer_schema_from_csv(path, &inference_options)?;
+fn print_schema(schema: &InferredTableSchema, table: &str) {
+    println!("Columns:");
+    for col in &schema.columns {
+        println!("  {}: {}", col.ddl_name, col.exasol_type.to_ddl_type());
+    }
+
+    let (schema_name, table_name) = super::parse_table_name(table);
+    println!();
+    println!("{}", schema.to_ddl(table_name, schema_name));
+}
 
-    let mut conn = args.conn.connect().await?;
+/// Gets the target table ready for loading according to the write mode.
+async fn prepare_target(
+    conn: &mut exarrow_rs::Connection,
+    schema: &InferredTableSchema,
+    table: &str,
+    mode: WriteMode,
+) -> anyhow::Result<()> {
+    let (schema_name, table_name) = super::parse_table_name(table);
+    match mode {
+        WriteMode::Append => create_table_if_not_exists(conn, schema, table).await?,
+        WriteMode::Truncate => {
+            create_table_if_not_exists(conn, schema, table).await?;
+            conn.execute_update(format!("TRUNCATE TABLE {table}"))
+                .await?;
+        }
+        WriteMode::Replace => {
+            conn.execute_update(format!("DROP TABLE IF EXISTS {table}"))
+                .await?;
+            conn.execute_update(schema.to_ddl(table_name, schema_name))
+                .await?;
+        }
+        WriteMode::Create => {
+            conn.execute_update(schema.to_ddl(table_name, schema_name))
+                .await?;
+        }
+    }
+
+    Ok(())
+}
 
-    let (schema_name, table_name) = super::parse_table_name(&args.table);
+async fn create_table_if_not_exists(
+    conn: &mut exarrow_rs::Connection,
+    schema: &InferredTableSchema,
+    table: &str,
+) -> anyhow::Result<()> {
+    let (schema_name, table_name) = super::parse_table_name(table);
     let ddl = schema.to_ddl(table_name, schema_name).replacen(
         "CREATE TABLE",
         "CREATE TABLE IF NOT EXISTS",
@@ -102,6 +1616,26 @@ async fn csv_import(path: &std::path::Path, args: &UploadArgs) -> anyhow::Result
     );
     conn.execute(&ddl).await?;
 
+    Ok(())
+}
+
+async fn parquet_import(
+    conn: &mut exarrow_rs::Connection,
+    path: &Path,
+    plan: &ImportPlan,
+) -> anyhow::Result<u64> {
+    let mut options = exarrow_rs::ParquetImportOptions::new()
+        .with_column_name_mode(ColumnNameMode::Quoted)
+        .with_native_parquet(Some(false));
+    if let Some(columns) = &plan.columns {
+        options = options.with_columns(columns.clone());
+    }
+
+    Ok(conn.import_from_parquet(&plan.table, path, options).await?)
+}
+
+/// The options Exasol reads CSV input with, following the CSV arguments.
+fn csv_import_options(plan: &ImportPlan, args: &UploadArgs) -> exarrow_rs::CsvImportOptions {
     let mut import_options = exarrow_rs::CsvImportOptions::new()
         .column_separator(args.delimiter)
         .column_delimiter(args.quote)
@@ -110,12 +1644,260 @@ async fn csv_import(path: &std::path::Path, args: &UploadArgs) -> anyhow::Result
     if !args.null_value.is_empty() {
         import_options = import_options.null_value(&args.null_value);
     }
+    if let Some(columns) = &plan.columns {
+        import_options = import_options.columns(columns.clone());
+    }
+    import_options
+}
+
+async fn csv_import(
+    conn: &mut exarrow_rs::Connection,
+    path: &Path,
+    plan: &ImportPlan,
+    args: &UploadArgs,
+) -> anyhow::Result<FileLoad> {
+    // The file is streamed to Exasol, decompressed on the fly if it is compressed.
+    let source = crate::compression::open(path)?;
+    plan.progress.watch(source.counter());
+    let counter = source.is_compressed().then(|| source.counter());
+    let source = plan.progress.rows(source, header_lines(args));
 
     let rows = conn
-        .import_csv_from_file(&args.table, path, import_options)
+        .import_csv_from_stream(
+            &plan.table,
+            crate::compression::stream(source),
+            csv_import_options(plan, args),
+        )
         .await?;
 
-    println!("Imported {rows} rows");
+    Ok(FileLoad {
+        rows,
+        bytes: counter.map(|c| c.counts()),
+    })
+}
+
+/// The number of lines before the first row of CSV input.
+fn header_lines(args: &UploadArgs) -> u64 {
+    if args.no_header {
+        0
+    } else {
+        1
+    }
+}
 
-    Ok(())
+/// The size of an input file on disk, for the progress report; 0 if it is unknown.
+fn file_size(path: &Path) -> u64 {
+    std::fs::metadata(path).map_or(0, |m| m.len())
+}
+
+/// Prints the row count per file (for multi-file uploads) followed by the total, and
+/// the compressed and uncompressed sizes when compressed files were read.
+fn print_summary(results: &[(&Path, FileLoad)]) {
+    let total: u64 = results.iter().map(|(_, load)| load.rows).sum();
+    let bytes = results
+        .iter()
+        .filter_map(|(_, load)| load.bytes)
+        .reduce(|a, b| ByteCounts {
+            compressed: a.compressed + b.compressed,
+            uncompressed: a.uncompressed + b.uncompressed,
+        });
+
+    if results.len() == 1 {
+        println!("Imported {total} rows");
+    } else {
+        for (path, load) in results {
+            match load.bytes {
+                Some(b) => println!(
+                    "  {}: {} rows ({} bytes compressed, {} bytes uncompressed)",
+                    path.display(),
+                    load.rows,
+                    b.compressed,
+                    b.uncompressed
+                ),
+                None => println!("  {}: {} rows", path.display(), load.rows),
+            }
+        }
+        println!("Imported {total} rows from {} files", results.len());
+    }
+
+    if let Some(b) = bytes {
+        println!(
+            "Read {} bytes compressed, {} bytes uncompressed",
+            b.compressed, b.uncompressed
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::test_upload_args;
+    use exarrow_rs::types::{ExasolType, InferredColumn};
+
+    fn schema(file: &str, columns: &[(&str, ExasolType)]) -> InferredTableSchema {
+        InferredTableSchema {
+            columns: columns
+                .iter()
+                .map(|(name, ty)| InferredColumn {
+                    original_name: name.to_string(),
+                    ddl_name: format!("\"{name}\""),
+                    exasol_type: ty.clone(),
+                    nullable: false,
+                })
+                .collect(),
+            source_files: vec![PathBuf::from(file)],
+        }
+    }
+
+    #[test]
+    fn target_columns_must_cover_every_loaded_column() {
+        let int = ExasolType::Decimal {
+            precision: 18,
+            scale: 0,
+        };
+        let table = |names: &[&str]| -> Vec<crate::table::TableColumn> {
+            names
+                .iter()
+                .map(|name| crate::table::TableColumn {
+                    name: name.to_string(),
+                    column_type: "DECIMAL(18,0)".into(),
+                })
+                .collect()
+        };
+        let file = schema("a.csv", &[("id", int.clone()), ("email", int)]);
+
+        assert_eq!(
+            target_column_names(&file, &table(&["EMAIL", "ID"]), "s.t").unwrap(),
+            vec!["\"ID\"", "\"EMAIL\""]
+        );
+        let error = target_column_names(&file, &table(&["ID", "NAME"]), "s.t").unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .starts_with("s.t has no column for email;"),
+            "got: {error}"
+        );
+    }
+
+    #[test]
+    fn merge_schemas_widens_column_types() {
+        let int = ExasolType::Decimal {
+            precision: 18,
+            scale: 0,
+        };
+        let merged = merge_schemas(vec![
+            schema("a.csv", &[("id", int.clone()), ("score", int)]),
+            schema(
+                "b.csv",
+                &[("id", ExasolType::Double), ("score", ExasolType::Double)],
+            ),
+        ])
+        .unwrap();
+
+        assert_eq!(merged.columns[0].exasol_type, ExasolType::Double);
+        assert_eq!(merged.columns[1].exasol_type, ExasolType::Double);
+        assert_eq!(merged.source_files.len(), 2);
+    }
+
+    #[test]
+    fn merge_schemas_rejects_different_column_names() {
+        let err = merge_schemas(vec![
+            schema("a.csv", &[("id", ExasolType::Double)]),
+            schema("b.csv", &[("key", ExasolType::Double)]),
+        ])
+        .unwrap_err()
+        .to_string();
+
+        assert!(err.contains("schema mismatch"), "got: {err}");
+        assert!(err.contains("b.csv"), "got: {err}");
+    }
+
+    #[test]
+    fn merge_schemas_rejects_different_column_counts() {
+        let err = merge_schemas(vec![
+            schema("a.csv", &[("id", ExasolType::Double)]),
+            schema(
+                "b.csv",
+                &[("id", ExasolType::Double), ("extra", ExasolType::Boolean)],
+            ),
+        ])
+        .unwrap_err()
+        .to_string();
+
+        assert!(err.contains("[id, extra]"), "got: {err}");
+    }
+
+    #[test]
+    fn merge_keys_resolve_to_ddl_names() {
+        let schema = schema(
+            "a.csv",
+            &[
+                ("id", ExasolType::Double),
+                ("Valid_From", ExasolType::Date),
+                ("value", ExasolType::Double),
+            ],
+        );
+        let keys = resolve_merge_keys(&schema, &["id".into(), "valid_from".into()]).unwrap();
+        assert_eq!(keys, vec!["\"id\"", "\"Valid_From\""]);
+    }
+
+    #[test]
+    fn merge_keys_reject_unknown_column() {
+        let schema = schema("a.csv", &[("id", ExasolType::Double)]);
+        let err = resolve_merge_keys(&schema, &["key".into()]).unwrap_err();
+        assert!(
+            err.to_string().contains("merge column key not found"),
+            "got: {err}"
+        );
+    }
+
+    fn bucketfs(read_password: Option<&str>) -> BucketFsClient {
+        BucketFsClient::new(crate::config::BfsConnection {
+            host: "exa".into(),
+            port: 2581,
+            bucket: "default".into(),
+            write_password: Some("w".into()),
+            read_password: read_password.map(String::from),
+            tls: true,
+            validate_certificate: true,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn bucketfs_import_reads_csv_with_the_csv_arguments() {
+        let args = test_upload_args(&["--delimiter", ";", "--null-value", "NA"]);
+        let columns = vec!["\"ID\"".to_string(), "\"NAME\"".to_string()];
+        let sql = bucketfs_import_sql(
+            FileFormat::Csv,
+            "s.t",
+            Some(&columns),
+            &args,
+            &bucketfs(Some("it's")),
+            "exapump-1-0/data.csv",
+        );
+        assert_eq!(
+            sql,
+            "IMPORT INTO s.t (\"ID\", \"NAME\") FROM CSV AT 'https://exa:2581/default' \
+             USER 'r' IDENTIFIED BY 'it''s' FILE 'exapump-1-0/data.csv' \
+             COLUMN SEPARATOR = ';' COLUMN DELIMITER = '\"' SKIP = 1 NULL = 'NA'"
+        );
+    }
+
+    #[test]
+    fn bucketfs_import_of_parquet_from_a_public_bucket() {
+        let sql = bucketfs_import_sql(
+            FileFormat::Parquet,
+            "s.t",
+            None,
+            &test_upload_args(&[]),
+            &bucketfs(None),
+            "exapump-1-0/data.parquet",
+        );
+        assert_eq!(
+            sql,
+            "IMPORT INTO s.t FROM PARQUET AT 'https://exa:2581/default' \
+             FILE 'exapump-1-0/data.parquet'"
+        );
+    }
 }
@@ -0,0 +1,306 @@
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::task::{ready, Context, Poll};
+
+use anyhow::{Context as _, Result};
+use tokio::io::{AsyncRead, ReadBuf};
+
+/// A compression codec recognised from the last extension of a file name, as in
+/// `data.csv.gz`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Compression {
+    Gzip,
+    Zstd,
+    Bzip2,
+    Xz,
+}
+
+impl Compression {
+    pub fn from_path(path: &Path) -> Option<Compression> {
+        let ext = path.extension()?.to_str()?;
+        match ext.to_ascii_lowercase().as_str() {
+            "gz" | "gzip" => Some(Compression::Gzip),
+            "zst" | "zstd" => Some(Compression::Zstd),
+            "bz2" | "bzip2" => Some(Compression::Bzip2),
+            "xz" => Some(Compression::Xz),
+            _ => None,
+        }
+    }
+}
+
+/// The bytes read from a compressed file and the bytes they decompressed into.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ByteCounts {
+    pub compressed: u64,
+    pub uncompressed: u64,
+}
+
+/// Shared counters that keep track of a [`Source`] while it is being read elsewhere.
+#[derive(Clone, Default)]
+pub struct ByteCounter {
+    compressed: Arc<AtomicU64>,
+    uncompressed: Arc<AtomicU64>,
+}
+
+impl ByteCounter {
+    pub fn counts(&self) -> ByteCounts {
+        ByteCounts {
+            compressed: self.compressed.load(Ordering::Relaxed),
+            uncompressed: self.uncompressed.load(Ordering::Relaxed),
+        }
+    }
+}
+
+struct Counting<R> {
+    inner: R,
+    count: Arc<AtomicU64>,
+}
+
+impl<R: Read> Read for Counting<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.count.fetch_add(n as u64, Ordering::Relaxed);
+        Ok(n)
+    }
+}
+
+/// The contents of an input file, decompressed on the fly if its name ends in a
+/// compression extension.
+pub struct Source {
+    reader: Box<dyn Read + Send>,
+    counter: ByteCounter,
+    compression: Option<Compression>,
+}
+
+impl Source {
+    /// The counters of this source; only meaningful for compressed files.
+    pub fn counter(&self) -> ByteCounter {
+        self.counter.clone()
+    }
+
+    pub fn is_compressed(&self) -> bool {
+        self.compression.is_some()
+    }
+}
+
+impl Read for Source {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.reader.read(buf)
+    }
+}
+
+/// Opens `path` for reading, decompressing it according to its extension.
+pub fn open(path: &Path) -> Result<Source> {
+    let file = File::open(path).with_context(|| format!("failed to open {}", path.display()))?;
+    let counter = ByteCounter::default();
+    let compression = Compression::from_path(path);
+
+    let raw = BufReader::new(Counting {
+        inner: file,
+        count: Arc::clone(&counter.compressed),
+    });
+    let decoded: Box<dyn Read + Send> = match compression {
+        None => Box::new(raw),
+        Some(Compression::Gzip) => Box::new(flate2::read::MultiGzDecoder::new(raw)),
+        Some(Compression::Zstd) => Box::new(zstd::stream::read::Decoder::with_buffer(raw)?),
+        Some(Compression::Bzip2) => Box::new(bzip2::read::MultiBzDecoder::new(raw)),
+        Some(Compression::Xz) => Box::new(liblzma::read::XzDecoder::new_multi_decoder(raw)),
+    };
+
+    Ok(Source {
+        reader: Box::new(Counting {
+            inner: decoded,
+            count: Arc::clone(&counter.uncompressed),
+        }),
+        counter,
+        compression,
+    })
+}
+
+/// An async reader over chunks read from a [`Source`] on a blocking thread. A read
+/// error, such as a corrupt compressed file, is passed on to the reading side, so that
+/// the import fails instead of loading a truncated file.
+pub struct SourceStream {
+    chunks: tokio::sync::mpsc::Receiver<io::Result<Vec<u8>>>,
+    current: Vec<u8>,
+    position: usize,
+}
+
+impl AsyncRead for SourceStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        while self.position == self.current.len() {
+            match ready!(self.chunks.poll_recv(cx)) {
+                Some(Ok(chunk)) => {
+                    self.current = chunk;
+                    self.position = 0;
+                }
+                Some(Err(e)) => return Poll::Ready(Err(e)),
+                None => return Poll::Ready(Ok(())),
+            }
+        }
+
+        let n = buf.remaining().min(self.current.len() - self.position);
+        let start = self.position;
+        buf.put_slice(&self.current[start..start + n]);
+        self.position += n;
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// Streams the contents of `source` to an async reader, reading it on a blocking
+/// thread so that decompression or encoding does not stall the runtime. Reading stops
+/// early once the stream is dropped.
+pub fn stream(mut source: impl Read + Send + 'static) -> SourceStream {
+    const CHUNK_SIZE: usize = 64 * 1024;
+    const CHUNKS_IN_FLIGHT: usize = 4;
+
+    let (sender, chunks) = tokio::sync::mpsc::channel(CHUNKS_IN_FLIGHT);
+    tokio::task::spawn_blocking(move || loop {
+        let mut chunk = vec![0; CHUNK_SIZE];
+        let chunk = match source.read(&mut chunk) {
+            Ok(0) => return,
+            Ok(n) => {
+                chunk.truncate(n);
+                Ok(chunk)
+            }
+            Err(e) => Err(e),
+        };
+        let failed = chunk.is_err();
+        if sender.blocking_send(chunk).is_err() || failed {
+            return;
+        }
+    });
+
+    SourceStream {
+        chunks,
+        current: Vec::new(),
+        position: 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::path::PathBuf;
+
+    const CSV: &str = "id,name\n1,alice\n2,bob\n";
+
+    fn write_compressed(dir: &Path, name: &str) -> PathBuf {
+        let path = dir.join(name);
+        let file = File::create(&path).unwrap();
+        match Compression::from_path(&path).unwrap() {
+            Compression::Gzip => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(file, flate2::Compression::default());
+                encoder.write_all(CSV.as_bytes()).unwrap();
+                encoder.finish().unwrap();
+            }
+            Compression::Zstd => {
+                let mut encoder = zstd::stream::write::Encoder::new(file, 0).unwrap();
+                encoder.write_all(CSV.as_bytes()).unwrap();
+                encoder.finish().unwrap();
+            }
+            Compression::Bzip2 => {
+                let mut encoder = bzip2::write::BzEncoder::new(file, bzip2::Compression::default());
+                encoder.write_all(CSV.as_bytes()).unwrap();
+                encoder.finish().unwrap();
+            }
+            Compression::Xz => {
+                let mut encoder = liblzma::write::XzEncoder::new(file, 6);
+                encoder.write_all(CSV.as_bytes()).unwrap();
+                encoder.finish().unwrap();
+            }
+        }
+        path
+    }
+
+    #[test]
+    fn compression_is_detected_from_last_extension() {
+        assert_eq!(
+            Compression::from_path(Path::new("a.csv.gz")),
+            Some(Compression::Gzip)
+        );
+        assert_eq!(
+            Compression::from_path(Path::new("a.csv.ZST")),
+            Some(Compression::Zstd)
+        );
+        assert_eq!(
+            Compression::from_path(Path::new("a.csv.bz2")),
+            Some(Compression::Bzip2)
+        );
+        assert_eq!(
+            Compression::from_path(Path::new("a.csv.xz")),
+            Some(Compression::Xz)
+        );
+        assert_eq!(Compression::from_path(Path::new("a.csv")), None);
+    }
+
+    #[test]
+    fn every_codec_decompresses() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["a.csv.gz", "a.csv.zst", "a.csv.bz2", "a.csv.xz"] {
+            let path = write_compressed(dir.path(), name);
+            let mut source = open(&path).unwrap();
+            let mut content = String::new();
+            source.read_to_string(&mut content).unwrap();
+            assert_eq!(content, CSV, "{name}");
+
+            let counts = source.counter().counts();
+            assert!(source.is_compressed());
+            assert_eq!(counts.uncompressed, CSV.len() as u64, "{name}");
+            assert_eq!(
+                counts.compressed,
+                std::fs::metadata(&path).unwrap().len(),
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn plain_file_is_read_as_is() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.csv");
+        std::fs::write(&path, CSV).unwrap();
+
+        let mut source = open(&path).unwrap();
+        let mut content = String::new();
+        source.read_to_string(&mut content).unwrap();
+        assert_eq!(content, CSV);
+        assert!(!source.is_compressed());
+    }
+
+    #[tokio::test]
+    async fn stream_delivers_decompressed_bytes() {
+        use tokio::io::AsyncReadExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_compressed(dir.path(), "a.csv.gz");
+        let mut reader = stream(open(&path).unwrap());
+
+        let mut content = String::new();
+        reader.read_to_string(&mut content).await.unwrap();
+        assert_eq!(content, CSV);
+    }
+
+    #[tokio::test]
+    async fn stream_passes_on_corrupt_data() {
+        use tokio::io::AsyncReadExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.csv.gz");
+        std::fs::write(&path, b"not gzip at all").unwrap();
+        let mut reader = stream(open(&path).unwrap());
+
+        let mut content = Vec::new();
+        assert!(reader.read_to_end(&mut content).await.is_err());
+    }
+}
@@ -0,0 +1,199 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+
+/// Expand the `upload` file arguments into a de-duplicated list of files, keeping the
+/// order of the arguments and sorting the files found in each directory or glob.
+///
+/// Each argument is handled as follows:
+/// - an existing file is taken as-is;
+/// - an existing directory contributes every file with a supported extension
+///   (descending into subdirectories only when `recursive` is set);
+/// - anything else containing glob metacharacters (`*`, `?`, `[`) is expanded as a
+///   glob pattern, so quoted patterns like `'landing/**/*.parquet'` work even where
+///   the shell does not expand them.
+///
+/// Files listed explicitly or matched by a glob are returned regardless of extension,
+/// so an unsupported file is reported by format detection rather than silently skipped.
+pub fn expand_inputs(inputs: &[String], recursive: bool) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+
+    for input in inputs {
+        let path = Path::new(input);
+
+        if path.is_file() {
+            files.push(path.to_path_buf());
+        } else if path.is_dir() {
+            let found = list_directory(path, recursive)?;
+            if found.is_empty() {
+                bail!("no supported files found in directory: {}", path.display());
+            }
+            files.extend(found);
+        } else if is_glob_pattern(input) {
+            let found = expand_glob(input)?;
+            if found.is_empty() {
+                bail!("no files match pattern: {input}");
+            }
+            files.extend(found);
+        } else {
+            bail!("file not found: {}", path.display());
+        }
+    }
+
+    let mut seen = HashSet::new();
+    files.retain(|f| seen.insert(f.clone()));
+
+    Ok(files)
+}
+
+fn is_glob_pattern(input: &str) -> bool {
+    input.contains(['*', '?', '['])
+}
+
+fn expand_glob(pattern: &str) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in glob::glob(pattern).with_context(|| format!("invalid glob pattern: {pattern}"))? {
+        let path = entry?;
+        if path.is_file() {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// List files with a supported extension in `dir`, sorted by path.
+fn list_directory(dir: &Path, recursive: bool) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+
+    while let Some(current) = pending.pop() {
+        let entries = std::fs::read_dir(&current)
+            .with_context(|| format!("failed to read directory {}", current.display()))?;
+
+        for entry in entries {
+            let path = entry?.path();
+            if path.is_dir() {
+                if recursive {
+                    pending.push(path);
+                }
+            } else if crate::format::detect_from_path(&path).is_ok() {
+                files.push(path);
+            }
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn touch(path: &Path) {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).unwrap();
+        }
+        std::fs::write(path, "id\n1\n").unwrap();
+    }
+
+    fn names(files: &[PathBuf], root: &Path) -> Vec<String> {
+        files
+            .iter()
+            .map(|f| {
+                f.strip_prefix(root)
+                    .unwrap()
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn literal_file_is_kept() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("a.csv");
+        touch(&file);
+
+        let files = expand_inputs(&[file.to_string_lossy().into_owned()], false).unwrap();
+        assert_eq!(files, vec![file]);
+    }
+
+    #[test]
+    fn missing_literal_file_fails() {
+        let err = expand_inputs(&["does/not/exist.csv".to_string()], false).unwrap_err();
+        assert!(err.to_string().contains("file not found"), "got: {err}");
+    }
+
+    #[test]
+    fn directory_lists_supported_files_only() {
+        let dir = tempfile::tempdir().unwrap();
+        touch(&dir.path().join("b.csv"));
+        touch(&dir.path().join("a.csv"));
+        touch(&dir.path().join("_SUCCESS"));
+        touch(&dir.path().join("nested/c.csv"));
+
+        let input = dir.path().to_string_lossy().into_owned();
+        let files = expand_inputs(&[input], false).unwrap();
+        assert_eq!(names(&files, dir.path()), vec!["a.csv", "b.csv"]);
+    }
+
+    #[test]
+    fn recursive_directory_includes_subdirectories() {
+        let dir = tempfile::tempdir().unwrap();
+        touch(&dir.path().join("a.csv"));
+        touch(&dir.path().join("nested/deeper/c.csv"));
+
+        let input = dir.path().to_string_lossy().into_owned();
+        let files = expand_inputs(&[input], true).unwrap();
+        assert_eq!(
+            names(&files, dir.path()),
+            vec!["a.csv", "nested/deeper/c.csv"]
+        );
+    }
+
+    #[test]
+    fn empty_directory_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().to_string_lossy().into_owned();
+        let err = expand_inputs(&[input], false).unwrap_err();
+        assert!(err.to_string().contains("no supported files"), "got: {err}");
+    }
+
+    #[test]
+    fn glob_pattern_is_expanded() {
+        let dir = tempfile::tempdir().unwrap();
+        touch(&dir.path().join("x/part-1.parquet"));
+        touch(&dir.path().join("y/z/part-2.parquet"));
+        touch(&dir.path().join("y/z/part-2.csv"));
+
+        let pattern = format!("{}/**/*.parquet", dir.path().display());
+        let files = expand_inputs(&[pattern], false).unwrap();
+        assert_eq!(
+            names(&files, dir.path()),
+            vec!["x/part-1.parquet", "y/z/part-2.parquet"]
+        );
+    }
+
+    #[test]
+    fn glob_without_matches_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let pattern = format!("{}/*.parquet", dir.path().display());
+        let err = expand_inputs(&[pattern], false).unwrap_err();
+        assert!(err.to_string().contains("no files match"), "got: {err}");
+    }
+
+    #[test]
+    fn duplicates_are_removed() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("a.csv");
+        touch(&file);
+
+        let literal = file.to_string_lossy().into_owned();
+        let pattern = format!("{}/*.csv", dir.path().display());
+        let files = expand_inputs(&[literal, pattern], false).unwrap();
+        assert_eq!(files, vec![file]);
+    }
+}
@@ -1,26 +1,82 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use anyhow::{bail, Result};
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum FileFormat {
     Parquet,
     Csv,
+    Json,
+    ArrowIpc,
 }
 
-const SUPPORTED_FORMATS: &str = ".parquet, .csv";
+impl std::fmt::Display for FileFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FileFormat::Parquet => write!(f, "Parquet"),
+            FileFormat::Csv => write!(f, "CSV"),
+            FileFormat::Json => write!(f, "JSON"),
+            FileFormat::ArrowIpc => write!(f, "Arrow IPC"),
+        }
+    }
+}
+
+const SUPPORTED_FORMATS: &str = ".parquet, .csv, .jsonl, .ndjson, .arrow, .arrows, .feather, .ipc \
+     (CSV and JSON optionally compressed: .gz, .zst, .bz2, .xz)";
 
 /// Returns an error listing supported formats when the extension is unrecognized.
+///
+/// A compression extension is looked through, so `data.csv.gz` is CSV; Parquet files
+/// and Arrow IPC files compress their data internally and are not accepted compressed.
 pub fn detect_from_path(path: &Path) -> Result<FileFormat> {
-    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+    let compression = crate::compression::Compression::from_path(path);
+    let inner = match compression {
+        Some(_) => Path::new(path.file_stem().unwrap_or_default()),
+        None => path,
+    };
+    let ext = inner.extension().and_then(|e| e.to_str()).unwrap_or("");
 
     match ext.to_ascii_lowercase().as_str() {
+        "parquet" if compression.is_some() => bail!(
+            "compressed Parquet files are not supported: {}. Parquet compresses its data internally",
+            path.display()
+        ),
+        "arrow" | "arrows" | "feather" | "ipc" if compression.is_some() => bail!(
+            "compressed Arrow IPC files are not supported: {}. Arrow IPC compresses its data internally",
+            path.display()
+        ),
         "parquet" => Ok(FileFormat::Parquet),
         "csv" => Ok(FileFormat::Csv),
+        "jsonl" | "ndjson" => Ok(FileFormat::Json),
+        "arrow" | "arrows" | "feather" | "ipc" => Ok(FileFormat::ArrowIpc),
         _ => bail!("file format {ext:?} is not supported. Supported formats: {SUPPORTED_FORMATS}"),
     }
 }
 
+/// Detects the format of every path and returns it if all paths agree.
+///
+/// Fails on the first unsupported extension, or when the set mixes formats, naming
+/// one file of each format so the user can see where the mix comes from.
+pub fn detect_common(paths: &[PathBuf]) -> Result<FileFormat> {
+    let Some(first) = paths.first() else {
+        bail!("no files to upload");
+    };
+    let format = detect_from_path(first)?;
+
+    for path in &paths[1..] {
+        let other = detect_from_path(path)?;
+        if other != format {
+            bail!(
+                "cannot mix file formats in one upload: {} is {format}, but {} is {other}",
+                first.display(),
+                path.display()
+            );
+        }
+    }
+
+    Ok(format)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -65,9 +121,67 @@ mod tests {
         assert_eq!(result.unwrap(), FileFormat::Csv);
     }
 
+    #[test]
+    fn compressed_csv_returns_csv() {
+        for name in ["data.csv.gz", "data.CSV.zst", "data.csv.bz2", "data.csv.xz"] {
+            let result = detect_from_path(Path::new(name));
+            assert_eq!(result.unwrap(), FileFormat::Csv, "{name}");
+        }
+    }
+
+    #[test]
+    fn json_lines_extensions_return_json() {
+        for name in ["data.jsonl", "data.NDJSON", "data.jsonl.gz"] {
+            let result = detect_from_path(Path::new(name));
+            assert_eq!(result.unwrap(), FileFormat::Json, "{name}");
+        }
+    }
+
+    #[test]
+    fn arrow_ipc_extensions_return_arrow_ipc() {
+        for name in ["data.arrow", "data.arrows", "data.FEATHER", "data.ipc"] {
+            let result = detect_from_path(Path::new(name));
+            assert_eq!(result.unwrap(), FileFormat::ArrowIpc, "{name}");
+        }
+        assert!(detect_from_path(Path::new("data.feather.gz")).is_err());
+    }
+
+    #[test]
+    fn compressed_parquet_returns_error() {
+        let msg = detect_from_path(Path::new("data.parquet.gz"))
+            .unwrap_err()
+            .to_string();
+        assert!(msg.contains("compressed Parquet"), "got: {msg}");
+    }
+
+    #[test]
+    fn compression_without_format_returns_error() {
+        assert!(detect_from_path(Path::new("data.gz")).is_err());
+    }
+
     #[test]
     fn no_extension_returns_error() {
         let result = detect_from_path(Path::new("data"));
         assert!(result.is_err());
     }
+
+    #[test]
+    fn common_format_of_uniform_set() {
+        let paths = vec![PathBuf::from("a.csv"), PathBuf::from("b.CSV")];
+        assert_eq!(detect_common(&paths).unwrap(), FileFormat::Csv);
+    }
+
+    #[test]
+    fn common_format_rejects_mixed_set() {
+        let paths = vec![PathBuf::from("a.csv"), PathBuf::from("b.parquet")];
+        let msg = detect_common(&paths).unwrap_err().to_string();
+        assert!(msg.contains("cannot mix file formats"), "got: {msg}");
+        assert!(msg.contains("b.parquet is Parquet"), "got: {msg}");
+    }
+
+    #[test]
+    fn common_format_rejects_unsupported_member() {
+        let paths = vec![PathBuf::from("a.csv"), PathBuf::from("b.txt")];
+        assert!(detect_common(&paths).is_err());
+    }
 }
@@ -0,0 +1,397 @@
+use std::fmt;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock};
+
+use anyhow::{bail, Context, Result};
+use arrow::array::{Array, StringArray};
+use arrow::datatypes::{DataType, Field, Schema};
+use exarrow_rs::types::{
+    format_column_name, CsvInferenceOptions, ExasolType, InferredColumn, InferredTableSchema,
+    TypeMapper,
+};
+use regex::RegexSet;
+
+use crate::cli::UploadArgs;
+
+/// The value patterns CSV type inference recognizes, in the order of their kind bits.
+/// They mirror the patterns arrow-csv infers column types with, which it does not
+/// expose; `kinds_agree_with_arrow_inference` checks that both still agree.
+static VALUE_PATTERNS: LazyLock<RegexSet> = LazyLock::new(|| {
+    RegexSet::new([
+        r"(?i)^(true)$|^(false)$(?-i)",
+        r"^-?(\d+)$",
+        r"^-?((\d*\.\d+|\d+\.\d*)([eE][-+]?\d+)?|\d+([eE][-+]?\d+))$",
+        r"^\d{4}-\d\d-\d\d$",
+        r"^\d{4}-\d\d-\d\d[T ]\d\d:\d\d:\d\d(?:[^\d\.].*)?$",
+        r"^\d{4}-\d\d-\d\d[T ]\d\d:\d\d:\d\d\.\d{1,3}(?:[^\d].*)?$",
+        r"^\d{4}-\d\d-\d\d[T ]\d\d:\d\d:\d\d\.\d{1,6}(?:[^\d].*)?$",
+        r"^\d{4}-\d\d-\d\d[T ]\d\d:\d\d:\d\d\.\d{1,9}(?:[^\d].*)?$",
+    ])
+    .expect("value patterns are valid")
+});
+
+const TEXT: u16 = 1 << 8;
+
+/// Values longer than this are shortened when reported.
+const MAX_VALUE_LEN: usize = 40;
+
+/// The value that settled the inferred type of a CSV column: the first value after
+/// which the column's type did not change anymore.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeEvidence {
+    /// The file column name.
+    pub column: String,
+    /// The kind of values the column was inferred from, such as `integer` or `text`.
+    pub kind: &'static str,
+    /// The file, the 1-based data row, and the value; `None` if every sampled value
+    /// was NULL.
+    pub source: Option<(PathBuf, u64, String)>,
+}
+
+impl fmt::Display for TypeEvidence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.source {
+            Some((path, row, value)) => write!(
+                f,
+                "{}: {} from {} row {row}: {value:?}",
+                self.column,
+                self.kind,
+                path.display()
+            ),
+            None => write!(f, "{}: no values in sample", self.column),
+        }
+    }
+}
+
+/// Tracks the value kinds seen in one column and where its type last changed.
+#[derive(Default)]
+struct ColumnKinds {
+    seen: u16,
+    source: Option<(PathBuf, u64, String)>,
+}
+
+impl ColumnKinds {
+    fn update(&mut self, path: &Path, row: u64, value: &str) {
+        let before = kind_name(self.seen);
+        self.seen |= value_kind(value);
+        if self.source.is_none() || kind_name(self.seen) != before {
+            self.source = Some((path.to_path_buf(), row, shorten(value)));
+        }
+    }
+}
+
+/// Returns the kind bit of a non-NULL CSV value.
+fn value_kind(value: &str) -> u16 {
+    if value.starts_with('"') {
+        return TEXT;
+    }
+    match VALUE_PATTERNS.matches(value).into_iter().next() {
+        Some(1) if value.len() >= 19 && value.parse::<i64>().is_err() => TEXT,
+        Some(m) => 1 << m,
+        None if matches!(value, "NaN" | "nan" | "inf" | "-inf") => 1 << 2,
+        None => TEXT,
+    }
+}
+
+/// Names the type the combined kind bits infer to, following arrow-csv's promotion
+/// rules: integers widen to decimals, dates to the most precise timestamp, and any
+/// other mix to text.
+fn kind_name(seen: u16) -> &'static str {
+    match seen {
+        0 => "null",
+        1 => "boolean",
+        2 => "integer",
+        4 | 6 => "decimal",
+        b if b & !0b1111_1000 == 0 => match b.leading_zeros() {
+            8..=11 => "timestamp",
+            _ => "date",
+        },
+        _ => "text",
+    }
+}
+
+fn shorten(value: &str) -> String {
+    match value.char_indices().nth(MAX_VALUE_LEN) {
+        Some((end, _)) => format!("{}...", &value[..end]),
+        None => value.to_string(),
+    }
+}
+
+/// Infers the schema of one CSV file, decompressing it on the fly if its name ends in
+/// a compression extension. Follows exarrow's `infer_schema_from_csv`, which can only
+/// read uncompressed files.
+pub fn infer_csv_schema(path: &Path, options: &CsvInferenceOptions) -> Result<InferredTableSchema> {
+    infer_csv_schema_from(crate::compression::open(path)?, path, options)
+}
+
+/// Infers the schema of CSV data read from `source`; `path` names the data in
+/// messages and in the schema.
+pub fn infer_csv_schema_from(
+    source: impl Read,
+    path: &Path,
+    options: &CsvInferenceOptions,
+) -> Result<InferredTableSchema> {
+    let mut format = arrow_csv::reader::Format::default()
+        .with_header(options.has_header)
+        .with_delimiter(options.delimiter);
+    if let Some(quote) = options.quote {
+        format = format.with_quote(quote);
+    }
+    if let Some(escape) = options.escape {
+        format = format.with_escape(escape);
+    }
+
+    let (schema, records_read) = format
+        .infer_schema(source, options.max_sample_records)
+        .with_context(|| format!("failed to infer CSV schema from {}", path.display()))?;
+    if records_read == 0 {
+        bail!("CSV file {} contains no data rows", path.display());
+    }
+
+    let columns = schema
+        .fields()
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let original_name = if options.has_header {
+                field.name().clone()
+            } else {
+                format!("col_{}", i + 1)
+            };
+            InferredColumn {
+                ddl_name: format_column_name(&original_name, options.column_name_mode),
+                exasol_type: TypeMapper::arrow_to_exasol(field.data_type())
+                    .unwrap_or(ExasolType::Varchar { size: 2_000_000 }),
+                nullable: field.is_nullable(),
+                original_name,
+            }
+        })
+        .collect();
+
+    Ok(InferredTableSchema {
+        columns,
+        source_files: vec![path.to_path_buf()],
+    })
+}
+
+/// Scans the CSV files the way type inference does and reports, for each column, the
+/// row that decided its inferred type. With `max_rows`, only the first rows of each
+/// file are scanned.
+pub fn csv_type_evidence(
+    paths: &[PathBuf],
+    args: &UploadArgs,
+    columns: &[String],
+    max_rows: Option<usize>,
+) -> Result<Vec<TypeEvidence>> {
+    let fields: Vec<Field> = (0..columns.len())
+        .map(|i| Field::new(format!("c{i}"), DataType::Utf8, true))
+        .collect();
+    let schema = Arc::new(Schema::new(fields));
+    let mut kinds: Vec<ColumnKinds> = columns.iter().map(|_| ColumnKinds::default()).collect();
+
+    for path in paths {
+        let mut builder = arrow_csv::ReaderBuilder::new(Arc::clone(&schema))
+            .with_header(!args.no_header)
+            .with_delimiter(args.delimiter as u8)
+            .with_quote(args.quote as u8);
+        if let Some(escape) = args.escape {
+            builder = builder.with_escape(escape as u8);
+        }
+        let source = crate::compression::open(path)?;
+        let source: Box<dyn Read> = if crate::reject::enabled(args) {
+            Box::new(crate::reject::WellFormedRows::new(source, args))
+        } else {
+            Box::new(source)
+        };
+
+        let mut row = 0u64;
+        'file: for batch in builder.build(source)? {
+            let batch = batch?;
+            let arrays: Vec<&StringArray> = batch
+                .columns()
+                .iter()
+                .map(|c| {
+                    c.as_any()
+                        .downcast_ref::<StringArray>()
+                        .expect("utf8 column")
+                })
+                .collect();
+            for i in 0..batch.num_rows() {
+                if max_rows.is_some_and(|max| row >= max as u64) {
+                    break 'file;
+                }
+                row += 1;
+                for (column, array) in kinds.iter_mut().zip(&arrays) {
+                    if !array.is_null(i) {
+                        column.update(path, row, array.value(i));
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(columns
+        .iter()
+        .zip(kinds)
+        .map(|(column, kinds)| TypeEvidence {
+            column: column.clone(),
+            kind: kind_name(kinds.seen),
+            source: kinds.source,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::test_upload_args;
+
+    fn names(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn value_kinds_follow_arrow_inference() {
+        assert_eq!(kind_name(value_kind("42")), "integer");
+        assert_eq!(kind_name(value_kind("4.2")), "decimal");
+        assert_eq!(kind_name(value_kind("TRUE")), "boolean");
+        assert_eq!(kind_name(value_kind("2024-01-31")), "date");
+        assert_eq!(kind_name(value_kind("2024-01-31 10:00:00")), "timestamp");
+        assert_eq!(kind_name(value_kind("abc")), "text");
+        assert_eq!(kind_name(value_kind("99999999999999999999")), "text");
+    }
+
+    #[test]
+    fn mixed_kinds_promote() {
+        assert_eq!(kind_name(value_kind("1") | value_kind("1.5")), "decimal");
+        assert_eq!(
+            kind_name(value_kind("2024-01-31") | value_kind("2024-01-31T10:00:00")),
+            "timestamp"
+        );
+        assert_eq!(kind_name(value_kind("1") | value_kind("true")), "text");
+    }
+
+    /// The kind arrow-csv infers for a column of `values`.
+    fn arrow_kind(values: &[&str]) -> &'static str {
+        let csv = format!("c\n{}\n", values.join("\n"));
+        let (schema, _) = arrow_csv::reader::Format::default()
+            .with_header(true)
+            .infer_schema(csv.as_bytes(), None)
+            .unwrap();
+        match schema.field(0).data_type() {
+            DataType::Null => "null",
+            DataType::Boolean => "boolean",
+            DataType::Int64 => "integer",
+            DataType::Float64 => "decimal",
+            DataType::Date32 => "date",
+            DataType::Timestamp(..) => "timestamp",
+            DataType::Utf8 => "text",
+            other => panic!("unexpected type {other}"),
+        }
+    }
+
+    #[test]
+    fn kinds_agree_with_arrow_inference() {
+        let samples: &[&[&str]] = &[
+            &["true", "FALSE"],
+            &["0", "-12", "9223372036854775807"],
+            &["9223372036854775808"],
+            &["1.5", "-.5", "5.", "1e5", "2.5E-3"],
+            &["NaN", "inf", "-inf"],
+            &["2026-10-18"],
+            &["2026-10-18T09:30:00", "2026-10-18 09:30:00Z"],
+            &["2026-10-18 09:30:00.123"],
+            &["2026-10-18 09:30:00.123456"],
+            &["2026-10-18 09:30:00.123456789"],
+            &["1", "1.5"],
+            &["2026-10-18", "2026-10-18T09:30:00"],
+            &["2026-10-18", "2026-10-18 09:30:00.123456"],
+            &["1", "true"],
+            &["1", "2026-10-18"],
+            &["abc", "1"],
+            &["10:30", "1;5", "1.2.3", "2026-1-8"],
+        ];
+        for values in samples {
+            let seen = values.iter().fold(0, |seen, v| seen | value_kind(v));
+            assert_eq!(kind_name(seen), arrow_kind(values), "values: {values:?}");
+        }
+    }
+
+    #[test]
+    fn evidence_points_at_the_row_that_widened_the_type() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.csv");
+        std::fs::write(&path, "id,amount,note\n1,10,\n2,20,\n3,20.5,\n4,7,\n").unwrap();
+
+        let evidence = csv_type_evidence(
+            std::slice::from_ref(&path),
+            &test_upload_args(&[]),
+            &names(&["id", "amount", "note"]),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(evidence[0].kind, "integer");
+        assert_eq!(evidence[0].source, Some((path.clone(), 1, "1".into())));
+        assert_eq!(evidence[1].kind, "decimal");
+        assert_eq!(evidence[1].source, Some((path.clone(), 3, "20.5".into())));
+        assert_eq!(evidence[2].kind, "null");
+        assert_eq!(evidence[2].to_string(), "note: no values in sample");
+    }
+
+    #[test]
+    fn evidence_respects_row_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.csv");
+        std::fs::write(&path, "amount\n1\n2\n2.5\n").unwrap();
+
+        let evidence = csv_type_evidence(
+            std::slice::from_ref(&path),
+            &test_upload_args(&[]),
+            &names(&["amount"]),
+            Some(2),
+        )
+        .unwrap();
+
+        assert_eq!(evidence[0].kind, "integer");
+        assert_eq!(
+            evidence[0].to_string(),
+            format!("amount: integer from {} row 1: \"1\"", path.display())
+        );
+    }
+
+    #[test]
+    fn schema_is_inferred_from_compressed_file() {
+        use std::io::Write;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.csv.gz");
+        let mut encoder = flate2::write::GzEncoder::new(
+            std::fs::File::create(&path).unwrap(),
+            flate2::Compression::default(),
+        );
+        encoder.write_all(b"id,amount\n1,2.5\n").unwrap();
+        encoder.finish().unwrap();
+
+        let schema = infer_csv_schema(&path, &CsvInferenceOptions::new()).unwrap();
+        assert_eq!(schema.columns[0].ddl_name, "\"id\"");
+        assert_eq!(schema.columns[1].exasol_type, ExasolType::Double);
+    }
+
+    #[test]
+    fn header_only_file_has_no_data_rows() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.csv");
+        std::fs::write(&path, "id,amount\n").unwrap();
+
+        let err = infer_csv_schema(&path, &CsvInferenceOptions::new()).unwrap_err();
+        assert!(err.to_string().contains("no data rows"), "got: {err}");
+    }
+
+    #[test]
+    fn long_values_are_shortened() {
+        let value = "x".repeat(50);
+        assert_eq!(shorten(&value), format!("{}...", "x".repeat(40)));
+    }
+}
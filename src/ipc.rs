@@ -0,0 +1,229 @@
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use arrow::error::ArrowError;
+use arrow::ipc::reader::{FileReader, StreamReader};
+use arrow::record_batch::RecordBatch;
+use exarrow_rs::types::{quote_identifier, InferredColumn, InferredTableSchema, TypeMapper};
+
+/// The magic bytes an Arrow IPC file starts with; the streaming format has none.
+const FILE_MAGIC: &[u8; 6] = b"ARROW1";
+
+/// The record batches of an Arrow IPC file, written either in the file format
+/// (`.arrow`, Feather v2) or in the streaming format.
+pub enum IpcReader {
+    File(FileReader<BufReader<File>>),
+    Stream(StreamReader<BufReader<File>>),
+}
+
+impl IpcReader {
+    pub fn open(path: &Path) -> Result<IpcReader> {
+        let mut file =
+            File::open(path).with_context(|| format!("failed to open {}", path.display()))?;
+        let mut magic = [0; FILE_MAGIC.len()];
+        let is_file = file.read_exact(&mut magic).is_ok() && &magic == FILE_MAGIC;
+        file.seek(SeekFrom::Start(0))?;
+
+        let reader = BufReader::new(file);
+        let result = if is_file {
+            FileReader::try_new(reader, None).map(IpcReader::File)
+        } else {
+            StreamReader::try_new(reader, None).map(IpcReader::Stream)
+        };
+        result.with_context(|| format!("failed to read Arrow IPC file {}", path.display()))
+    }
+
+    pub fn schema(&self) -> SchemaRef {
+        match self {
+            IpcReader::File(reader) => reader.schema(),
+            IpcReader::Stream(reader) => reader.schema(),
+        }
+    }
+}
+
+/// Yields the batches of the file, with dictionary-encoded columns decoded.
+impl Iterator for IpcReader {
+    type Item = Result<RecordBatch>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let batch = match self {
+            IpcReader::File(reader) => reader.next(),
+            IpcReader::Stream(reader) => reader.next(),
+        }?;
+        Some(
+            batch
+                .and_then(decode_dictionaries)
+                .context("failed to read Arrow IPC record batch"),
+        )
+    }
+}
+
+/// Infers the table schema of an Arrow IPC file from the schema it carries, without
+/// reading any data. Dictionary-encoded columns take the type of their values.
+pub fn infer_ipc_schema(path: &Path) -> Result<InferredTableSchema> {
+    let schema = IpcReader::open(path)?.schema();
+    let columns = schema
+        .fields()
+        .iter()
+        .map(|field| {
+            let exasol_type = TypeMapper::arrow_to_exasol(value_type(field.data_type()))
+                .with_context(|| {
+                    format!(
+                        "failed to map type of column {:?} in {}",
+                        field.name(),
+                        path.display()
+                    )
+                })?;
+            Ok(InferredColumn {
+                original_name: field.name().clone(),
+                ddl_name: quote_identifier(field.name()),
+                exasol_type,
+                nullable: field.is_nullable(),
+            })
+        })
+        .collect::<Result<_>>()?;
+
+    Ok(InferredTableSchema {
+        columns,
+        source_files: vec![path.to_path_buf()],
+    })
+}
+
+fn value_type(data_type: &DataType) -> &DataType {
+    match data_type {
+        DataType::Dictionary(_, values) => values,
+        other => other,
+    }
+}
+
+/// Replaces dictionary-encoded columns, such as pandas categoricals, with their values.
+fn decode_dictionaries(batch: RecordBatch) -> Result<RecordBatch, ArrowError> {
+    let schema = batch.schema();
+    if !schema
+        .fields()
+        .iter()
+        .any(|f| matches!(f.data_type(), DataType::Dictionary(..)))
+    {
+        return Ok(batch);
+    }
+
+    let (fields, columns): (Vec<_>, Vec<_>) = schema
+        .fields()
+        .iter()
+        .zip(batch.columns())
+        .map(|(field, column)| {
+            let data_type = value_type(field.data_type());
+            let column = arrow::compute::cast(column, data_type)?;
+            let field = Field::new(field.name(), data_type.clone(), field.is_nullable());
+            Ok((field, column))
+        })
+        .collect::<Result<Vec<_>, ArrowError>>()?
+        .into_iter()
+        .unzip();
+    RecordBatch::try_new(std::sync::Arc::new(Schema::new(fields)), columns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use arrow::array::{ArrayRef, DictionaryArray, Int32Array, StringArray};
+    use arrow::datatypes::Int8Type;
+    use arrow::ipc::writer::{FileWriter, StreamWriter};
+    use exarrow_rs::types::ExasolType;
+
+    fn batch() -> RecordBatch {
+        let kind: DictionaryArray<Int8Type> = vec!["a", "b", "a"].into_iter().collect();
+        RecordBatch::try_from_iter([
+            ("id", Arc::new(Int32Array::from(vec![1, 2, 3])) as ArrayRef),
+            (
+                "name",
+                Arc::new(StringArray::from(vec![Some("x, y"), None, Some("z")])) as ArrayRef,
+            ),
+            ("kind", Arc::new(kind) as ArrayRef),
+        ])
+        .unwrap()
+    }
+
+    fn write_file(path: &Path) {
+        let batch = batch();
+        let mut writer = FileWriter::try_new(File::create(path).unwrap(), &batch.schema()).unwrap();
+        writer.write(&batch).unwrap();
+        writer.write(&batch).unwrap();
+        writer.finish().unwrap();
+    }
+
+    fn write_stream(path: &Path) {
+        let batch = batch();
+        let mut writer =
+            StreamWriter::try_new(File::create(path).unwrap(), &batch.schema()).unwrap();
+        writer.write(&batch).unwrap();
+        writer.finish().unwrap();
+    }
+
+    fn csv(reader: IpcReader, selected: Option<Vec<usize>>) -> String {
+        let mut content = String::new();
+        crate::project::CsvRows::new(reader, selected)
+            .read_to_string(&mut content)
+            .unwrap();
+        content
+    }
+
+    #[test]
+    fn schema_is_taken_from_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.feather");
+        write_file(&path);
+
+        let schema = infer_ipc_schema(&path).unwrap();
+        let names: Vec<_> = schema.columns.iter().map(|c| c.ddl_name.as_str()).collect();
+        assert_eq!(names, vec!["\"id\"", "\"name\"", "\"kind\""]);
+        assert_eq!(
+            schema.columns[2].exasol_type,
+            TypeMapper::arrow_to_exasol(&DataType::Utf8).unwrap()
+        );
+        assert_ne!(
+            schema.columns[0].exasol_type,
+            ExasolType::Varchar { size: 2_000_000 }
+        );
+    }
+
+    #[test]
+    fn file_format_batches_are_encoded_as_csv() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.arrow");
+        write_file(&path);
+
+        let content = csv(IpcReader::open(&path).unwrap(), None);
+        assert_eq!(content.lines().count(), 6);
+        assert!(
+            content.starts_with("1,\"x, y\",a\n2,,b\n"),
+            "got: {content}"
+        );
+    }
+
+    #[test]
+    fn stream_format_is_detected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.arrows");
+        write_stream(&path);
+
+        let reader = IpcReader::open(&path).unwrap();
+        assert!(matches!(reader, IpcReader::Stream(_)));
+        assert_eq!(csv(reader, Some(vec![2, 0])), "a,1\nb,2\na,3\n");
+    }
+
+    #[test]
+    fn invalid_file_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.arrow");
+        std::fs::write(&path, b"not arrow").unwrap();
+
+        let err = IpcReader::open(&path).err().unwrap();
+        assert!(err.to_string().contains("Arrow IPC"), "got: {err}");
+    }
+}
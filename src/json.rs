@@ -0,0 +1,236 @@
+use std::io::{BufRead, BufReader, Read};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::{bail, Context, Result};
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use arrow_json::reader::{infer_json_schema_from_iterator, ValueIter};
+use exarrow_rs::types::InferredTableSchema;
+
+use crate::cli::NestedColumns;
+use crate::nested::NestedLayout;
+
+/// How the records of a set of JSON files map to table columns: the schema inferred
+/// from the records and the columns it is flattened into.
+pub struct JsonLayout {
+    schema: SchemaRef,
+    layout: NestedLayout,
+}
+
+impl JsonLayout {
+    /// Infers one schema across the records of all `paths`, reading at most
+    /// `max_records` records of each file.
+    pub fn infer(
+        paths: &[PathBuf],
+        max_records: Option<usize>,
+        nested: NestedColumns,
+    ) -> Result<JsonLayout> {
+        let sources = paths
+            .iter()
+            .map(|path| crate::compression::open(path).map(BufReader::new))
+            .collect::<Result<Vec<_>>>()?;
+        JsonLayout::infer_from(sources, max_records, nested)
+    }
+
+    /// Infers one schema across the records of all `sources`, reading at most
+    /// `max_records` records of each.
+    pub fn infer_from(
+        sources: Vec<impl BufRead>,
+        max_records: Option<usize>,
+        nested: NestedColumns,
+    ) -> Result<JsonLayout> {
+        let mut records = 0;
+        let values = sources
+            .into_iter()
+            .flat_map(|source| ValueIter::new(source, max_records))
+            .inspect(|_| records += 1);
+        let schema = infer_json_schema_from_iterator(values)
+            .context("failed to infer the schema of the JSON records")?;
+
+        if records == 0 {
+            bail!("JSON files contain no records");
+        }
+
+        let layout = NestedLayout::new(schema.fields(), nested)?;
+        Ok(JsonLayout {
+            schema: Arc::new(schema),
+            layout,
+        })
+    }
+
+    /// The table schema the records are loaded into.
+    pub fn table_schema(&self, paths: &[PathBuf]) -> InferredTableSchema {
+        self.layout.table_schema(paths)
+    }
+
+    /// How the objects and arrays of the records map to table columns.
+    pub fn nested(&self) -> &NestedLayout {
+        &self.layout
+    }
+
+    /// Reads newline-delimited JSON records into batches with one column per table
+    /// column, one batch at a time as the returned iterator advances. Fields missing
+    /// from a record are NULL; a field the schema does not know, which can only occur
+    /// when inference read a sample, fails the read rather than being dropped.
+    pub fn batches(&self, source: impl Read) -> Result<impl Iterator<Item = Result<RecordBatch>>> {
+        let reader = arrow_json::ReaderBuilder::new(Arc::clone(&self.schema))
+            .with_coerce_primitive(true)
+            .with_strict_mode(true)
+            .build(BufReader::new(source))?;
+        let layout = self.layout.clone();
+
+        Ok(reader.map(move |batch| {
+            let batch = batch.map_err(|e| {
+                let unknown_field = e.to_string().contains("missing from schema");
+                let e = anyhow::Error::new(e);
+                if unknown_field {
+                    e.context(
+                        "a JSON record has a field that was not in the records column types \
+                         were inferred from; raise --infer-rows or leave it out",
+                    )
+                } else {
+                    e
+                }
+            })?;
+            layout.apply(&batch)
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::Array;
+    use arrow::util::display::array_value_to_string;
+    use exarrow_rs::types::ExasolType;
+
+    const RECORDS: &str = concat!(
+        r#"{"id": 1, "customer": {"name": "alice", "address": {"city": "Berlin"}}, "tags": ["a", "b"]}"#,
+        "\n",
+        r#"{"id": 2.5, "customer": null}"#,
+        "\n",
+        "\n",
+        r#"{"id": 3, "customer": {"name": "carol"}, "note": "x"}"#,
+        "\n",
+    );
+
+    fn layout(nested: NestedColumns) -> (tempfile::TempDir, PathBuf, JsonLayout) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.jsonl");
+        std::fs::write(&path, RECORDS).unwrap();
+        let layout = JsonLayout::infer(std::slice::from_ref(&path), None, nested).unwrap();
+        (dir, path, layout)
+    }
+
+    fn names(layout: &JsonLayout) -> Vec<String> {
+        layout
+            .table_schema(&[])
+            .columns
+            .into_iter()
+            .map(|c| c.original_name)
+            .collect()
+    }
+
+    fn values(batch: &RecordBatch, column: usize) -> Vec<String> {
+        (0..batch.num_rows())
+            .map(|row| {
+                let array = batch.column(column);
+                if array.is_null(row) {
+                    "NULL".to_string()
+                } else {
+                    array_value_to_string(array, row).unwrap()
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn nested_objects_are_flattened() {
+        let (_dir, path, layout) = layout(NestedColumns::Flatten);
+        assert_eq!(
+            names(&layout),
+            vec![
+                "id",
+                "customer_name",
+                "customer_address_city",
+                "tags",
+                "note"
+            ]
+        );
+
+        let schema = layout.table_schema(std::slice::from_ref(&path));
+        assert_eq!(schema.columns[0].exasol_type, ExasolType::Double);
+        assert_eq!(schema.columns[2].ddl_name, "\"customer_address_city\"");
+
+        let batches = layout
+            .batches(std::fs::File::open(&path).unwrap())
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(values(&batches[0], 1), vec!["alice", "NULL", "carol"]);
+        assert_eq!(values(&batches[0], 2), vec!["Berlin", "NULL", "NULL"]);
+        assert_eq!(values(&batches[0], 3), vec![r#"["a","b"]"#, "NULL", "NULL"]);
+        assert_eq!(values(&batches[0], 4), vec!["NULL", "NULL", "x"]);
+    }
+
+    #[test]
+    fn nested_objects_can_be_kept_as_json() {
+        let (_dir, path, layout) = layout(NestedColumns::Json);
+        assert_eq!(names(&layout), vec!["id", "customer", "tags", "note"]);
+
+        let batches = layout
+            .batches(std::fs::File::open(&path).unwrap())
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(
+            values(&batches[0], 1),
+            vec![
+                r#"{"name":"alice","address":{"city":"Berlin"}}"#,
+                "NULL",
+                r#"{"name":"carol"}"#
+            ]
+        );
+    }
+
+    #[test]
+    fn record_limit_applies_per_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.jsonl");
+        std::fs::write(&path, "{\"a\": 1}\n{\"b\": 2}\n").unwrap();
+
+        let layout = JsonLayout::infer(&[path], Some(1), NestedColumns::Flatten).unwrap();
+        assert_eq!(names(&layout), vec!["a"]);
+    }
+
+    #[test]
+    fn field_after_the_sample_fails_the_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.jsonl");
+        std::fs::write(&path, "{\"a\": 1}\n{\"a\": 2, \"b\": 3}\n").unwrap();
+
+        let layout =
+            JsonLayout::infer(std::slice::from_ref(&path), Some(1), NestedColumns::Flatten)
+                .unwrap();
+        let err = layout
+            .batches(std::fs::File::open(&path).unwrap())
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap_err();
+        assert!(format!("{err:#}").contains("--infer-rows"), "got: {err:#}");
+        assert!(format!("{err:#}").contains("'b'"), "got: {err:#}");
+    }
+
+    #[test]
+    fn empty_file_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.jsonl");
+        std::fs::write(&path, "\n").unwrap();
+
+        let err = JsonLayout::infer(&[path], None, NestedColumns::Flatten)
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("no records"), "got: {err}");
+    }
+}
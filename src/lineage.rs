@@ -0,0 +1,262 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{bail, Result};
+use arrow::array::{ArrayRef, StringArray};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+use exarrow_rs::types::{quote_identifier, ExasolType, InferredColumn, InferredTableSchema};
+
+use crate::cli::ColumnNames;
+
+/// A column that `--add-column` or `--partition-columns` appends to every loaded row,
+/// such as the file the row came from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AddedColumn {
+    pub name: String,
+    pub value: AddedValue,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AddedValue {
+    /// `$FILE`: the path of the file the row was read from, as given.
+    File,
+    /// `$NOW`: the time the upload started, the same for every row.
+    Now,
+    /// Any other text, loaded as it is.
+    Constant(String),
+    /// The value of a Hive-style `key=value` directory in the path of the file, with
+    /// the type inferred from the values of all files (`--partition-columns`).
+    Partition {
+        key: String,
+        exasol_type: ExasolType,
+    },
+}
+
+impl AddedValue {
+    fn exasol_type(&self) -> ExasolType {
+        match self {
+            AddedValue::File => ExasolType::Varchar { size: 2000 },
+            AddedValue::Now => ExasolType::Timestamp {
+                with_local_time_zone: false,
+            },
+            AddedValue::Constant(value) => ExasolType::Varchar {
+                size: value.chars().count().max(1),
+            },
+            AddedValue::Partition { exasol_type, .. } => exasol_type.clone(),
+        }
+    }
+}
+
+/// Parse `--add-column` values of the form `name=value`.
+pub fn parse_added_columns(values: &[String]) -> Result<Vec<AddedColumn>> {
+    values
+        .iter()
+        .map(|value| match value.split_once('=') {
+            Some((name, value)) if !name.trim().is_empty() => Ok(AddedColumn {
+                name: name.trim().to_string(),
+                value: match value {
+                    "$FILE" => AddedValue::File,
+                    "$NOW" => AddedValue::Now,
+                    _ => AddedValue::Constant(value.to_string()),
+                },
+            }),
+            _ => bail!("invalid --add-column value '{value}', expected NAME=VALUE"),
+        })
+        .collect()
+}
+
+/// Appends the added columns to the schema of the loaded columns, named under `mode`
+/// like the file columns. Fails if one of them has the name of a loaded column.
+pub fn apply(
+    schema: &mut InferredTableSchema,
+    added: &[AddedColumn],
+    mode: ColumnNames,
+) -> Result<()> {
+    for column in added {
+        let ddl_name = quote_identifier(&crate::naming::table_column_name(&column.name, mode));
+        if schema
+            .columns
+            .iter()
+            .any(|c| c.ddl_name.eq_ignore_ascii_case(&ddl_name))
+        {
+            match column.value {
+                AddedValue::Partition { .. } => bail!(
+                    "partition column {} is already a column of the table",
+                    column.name
+                ),
+                _ => bail!(
+                    "--add-column {} is already a column of the table",
+                    column.name
+                ),
+            }
+        }
+        schema.columns.push(InferredColumn {
+            original_name: column.name.clone(),
+            ddl_name,
+            exasol_type: column.value.exasol_type(),
+            nullable: true,
+        });
+    }
+    Ok(())
+}
+
+/// The added columns of an upload, with the time it started.
+pub struct Lineage {
+    columns: Vec<AddedColumn>,
+    now: String,
+}
+
+impl Lineage {
+    /// Takes the start time of the upload from Exasol, so that `$NOW` agrees with the
+    /// database clock and session time zone.
+    pub async fn start(
+        conn: &mut exarrow_rs::Connection,
+        columns: Vec<AddedColumn>,
+    ) -> Result<Lineage> {
+        let mut now = String::new();
+        if columns.iter().any(|c| c.value == AddedValue::Now) {
+            let batches = conn
+                .query("SELECT TO_CHAR(CURRENT_TIMESTAMP, 'YYYY-MM-DD HH24:MI:SS.FF3')")
+                .await?;
+            let batch = batches
+                .iter()
+                .find(|b| b.num_rows() > 0)
+                .ok_or_else(|| anyhow::anyhow!("CURRENT_TIMESTAMP query returned no rows"))?;
+            now = arrow::util::display::array_value_to_string(batch.column(0), 0)?;
+        }
+        Ok(Lineage { columns, now })
+    }
+
+    /// Whether no columns are added.
+    pub fn is_empty(&self) -> bool {
+        self.columns.is_empty()
+    }
+
+    /// The values of the added columns for the rows of the file at `path`.
+    pub fn values(&self, path: &Path) -> Vec<String> {
+        self.columns
+            .iter()
+            .map(|column| match &column.value {
+                AddedValue::File => path.display().to_string(),
+                AddedValue::Now => self.now.clone(),
+                AddedValue::Constant(value) => value.clone(),
+                AddedValue::Partition { key, .. } => crate::partition::partition_value(path, key),
+            })
+            .collect()
+    }
+}
+
+/// Appends a column holding each of `values` in every row of `batch`.
+pub fn append_values(batch: &RecordBatch, values: &[String]) -> Result<RecordBatch> {
+    if values.is_empty() {
+        return Ok(batch.clone());
+    }
+
+    let schema = batch.schema();
+    let mut fields: Vec<Arc<Field>> = schema.fields().iter().cloned().collect();
+    let mut columns = batch.columns().to_vec();
+    for (i, value) in values.iter().enumerate() {
+        fields.push(Arc::new(Field::new(
+            format!("added_{i}"),
+            DataType::Utf8,
+            false,
+        )));
+        let array: ArrayRef = Arc::new(StringArray::from(vec![value.as_str(); batch.num_rows()]));
+        columns.push(array);
+    }
+    Ok(RecordBatch::try_new(
+        Arc::new(Schema::new(fields)),
+        columns,
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::Int64Array;
+
+    #[test]
+    fn placeholders_and_constants_are_parsed() {
+        let added = parse_added_columns(&[
+            "source_file=$FILE".into(),
+            "loaded_at=$NOW".into(),
+            "batch=2026-10".into(),
+        ])
+        .unwrap();
+        assert_eq!(added[0].value, AddedValue::File);
+        assert_eq!(added[1].value, AddedValue::Now);
+        assert_eq!(added[2].value, AddedValue::Constant("2026-10".into()));
+
+        assert!(parse_added_columns(&["=$FILE".into()]).is_err());
+        assert!(parse_added_columns(&["source_file".into()]).is_err());
+    }
+
+    #[test]
+    fn constants_may_contain_commas() {
+        let args = crate::cli::test_upload_args(&[
+            "--add-column",
+            "source=Acme, Inc.",
+            "--add-column",
+            "f=$FILE",
+        ]);
+        let added = parse_added_columns(&args.add_columns).unwrap();
+        assert_eq!(added.len(), 2);
+        assert_eq!(added[0].value, AddedValue::Constant("Acme, Inc.".into()));
+        assert_eq!(added[1].value, AddedValue::File);
+    }
+
+    #[test]
+    fn added_columns_follow_the_loaded_columns() {
+        let mut schema = InferredTableSchema {
+            columns: vec![InferredColumn {
+                original_name: "id".into(),
+                ddl_name: "\"id\"".into(),
+                exasol_type: ExasolType::Decimal {
+                    precision: 18,
+                    scale: 0,
+                },
+                nullable: true,
+            }],
+            source_files: Vec::new(),
+        };
+        let added =
+            parse_added_columns(&["source file=$FILE".into(), "loaded_at=$NOW".into()]).unwrap();
+        apply(&mut schema, &added, ColumnNames::Upper).unwrap();
+
+        let ddl = schema.to_ddl("T", None);
+        assert!(ddl.contains("\"SOURCE_FILE\" VARCHAR(2000)"), "got: {ddl}");
+        assert!(ddl.contains("\"LOADED_AT\" TIMESTAMP"), "got: {ddl}");
+
+        let clash = parse_added_columns(&["ID=x".into()]).unwrap();
+        assert!(apply(&mut schema, &clash, ColumnNames::Upper).is_err());
+    }
+
+    #[test]
+    fn values_fill_every_row() {
+        let batch = RecordBatch::try_from_iter([(
+            "id",
+            Arc::new(Int64Array::from(vec![1, 2])) as ArrayRef,
+        )])
+        .unwrap();
+        let lineage = Lineage {
+            columns: parse_added_columns(&["f=$FILE".into(), "t=$NOW".into()]).unwrap(),
+            now: "2026-10-18 09:30:00.000".into(),
+        };
+
+        let batch = append_values(&batch, &lineage.values(Path::new("data/a.csv"))).unwrap();
+        assert_eq!(batch.num_columns(), 3);
+        let files = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(files.value(1), "data/a.csv");
+        let times = batch
+            .column(2)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(times.value(0), "2026-10-18 09:30:00.000");
+    }
+}
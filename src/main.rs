@@ -1,18 +1,41 @@
+mod audit;
 mod cli;
 mod commands;
+mod compression;
 mod config;
 mod connection;
+mod expand;
 mod format;
+mod inference;
+mod ipc;
+mod json;
+mod lineage;
+mod manifest;
+mod mapping;
+mod naming;
+mod nested;
+mod overrides;
+mod partition;
+mod progress;
+mod project;
+mod reject;
+mod report;
+mod schema_diff;
 mod size;
 mod split;
+mod stdin;
+mod table;
 
 use std::io::Write;
+use std::process::ExitCode;
+use std::time::Instant;
 
 use clap::Parser;
-use cli::{Cli, Commands};
+use cli::{Cli, Commands, ReportFormat};
+use report::Report;
 
 #[tokio::main]
-async fn main() -> anyhow::Result<()> {
+async fn main() -> anyhow::Result<ExitCode> {
     rustls::crypto::ring::default_provider()
         .install_default()
         .expect("Failed to install default CryptoProvider");
@@ -21,32 +44,50 @@ async fn main() -> anyhow::Result<()> {
 
     let cli = Cli::parse();
 
-    match cli.command {
-        Some(Commands::Upload(args)) => {
-            commands::upload::run(args).await?;
+    let Some(command) = cli.command else {
+        let mut cmd = <Cli as clap::CommandFactory>::command();
+        cmd.print_help()?;
+        writeln!(std::io::stdout())?;
+        std::process::exit(2);
+    };
+
+    let mut report = Report::new(command.name());
+    let started = Instant::now();
+    let result = run(command, &mut report).await;
+    report.finish(&result, started.elapsed());
+
+    if cli.report.is_some() || cli.report_file.is_some() {
+        let format = cli.report.unwrap_or(ReportFormat::Json);
+        // The report is a by-product of the run: failing to write it must not hide
+        // how the command itself went.
+        if let Err(e) = report.emit(format, cli.report_file.as_deref()) {
+            eprintln!("Warning: {e:#}");
         }
-        Some(Commands::Sql(args)) => {
-            commands::sql::run(args).await?;
+    }
+    result
+}
+
+async fn run(command: Commands, report: &mut Report) -> anyhow::Result<ExitCode> {
+    match command {
+        Commands::Upload(args) => {
+            return commands::upload::run(args, report).await;
+        }
+        Commands::Sql(args) => {
+            commands::sql::run(args, report).await?;
         }
-        Some(Commands::Export(args)) => {
-            commands::export::run(args).await?;
+        Commands::Export(args) => {
+            commands::export::run(args, report).await?;
         }
-        Some(Commands::Interactive(args)) => {
+        Commands::Interactive(args) => {
             commands::interactive::run(args).await?;
         }
-        Some(Commands::Profile(args)) => {
+        Commands::Profile(args) => {
             commands::profile::run(args)?;
         }
-        Some(Commands::Bucketfs(args)) => {
+        Commands::Bucketfs(args) => {
             commands::bucketfs::run(args).await?;
         }
-        None => {
-            let mut cmd = <Cli as clap::CommandFactory>::command();
-            cmd.print_help()?;
-            writeln!(std::io::stdout())?;
-            std::process::exit(2);
-        }
     }
 
-    Ok(())
+    Ok(ExitCode::SUCCESS)
 }
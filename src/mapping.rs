@@ -0,0 +1,216 @@
+use anyhow::{bail, Result};
+use exarrow_rs::types::{quote_identifier, InferredTableSchema};
+
+/// How the file columns map to the table columns, resolved from `--columns` and `--map`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnMapping {
+    /// Indices of the file columns to load, in load order.
+    pub selected: Vec<usize>,
+    /// The file column name and the DDL name of its table column, in load order.
+    pub pairs: Vec<(String, String)>,
+}
+
+impl ColumnMapping {
+    /// Whether every file column is loaded, in file order.
+    pub fn keeps_all_columns(&self, column_count: usize) -> bool {
+        self.selected.iter().copied().eq(0..column_count)
+    }
+}
+
+/// Finds a file column by name, exactly first and then case-insensitively.
+pub fn find_file_column(schema: &InferredTableSchema, name: &str) -> Option<usize> {
+    let name = name.trim();
+    schema
+        .columns
+        .iter()
+        .position(|c| c.original_name == name)
+        .or_else(|| {
+            schema
+                .columns
+                .iter()
+                .position(|c| c.original_name.eq_ignore_ascii_case(name))
+        })
+}
+
+/// Parse `--map` values of the form `file_col=table_col`.
+pub fn parse_renames(values: &[String]) -> Result<Vec<(String, String)>> {
+    values
+        .iter()
+        .map(|value| match value.split_once('=') {
+            Some((from, to)) if !from.trim().is_empty() && !to.trim().is_empty() => {
+                Ok((from.trim().to_string(), to.trim().to_string()))
+            }
+            _ => bail!("invalid --map value '{value}', expected FILE_COLUMN=TABLE_COLUMN"),
+        })
+        .collect()
+}
+
+/// Applies `--columns` and `--map` to the inferred schema.
+///
+/// The returned schema describes the table columns to load, in load order, and is
+/// what the DDL is generated from; the mapping records which file column feeds
+/// which table column. Without `--columns`, every file column is kept in file order.
+pub fn apply(
+    schema: &InferredTableSchema,
+    columns: &[String],
+    renames: &[(String, String)],
+) -> Result<(InferredTableSchema, ColumnMapping)> {
+    let names = || {
+        schema
+            .columns
+            .iter()
+            .map(|c| c.original_name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    let selected: Vec<usize> = if columns.is_empty() {
+        (0..schema.columns.len()).collect()
+    } else {
+        columns
+            .iter()
+            .map(|name| {
+                find_file_column(schema, name).ok_or_else(|| {
+                    anyhow::anyhow!("column {name} not found in file columns [{}]", names())
+                })
+            })
+            .collect::<Result<_>>()?
+    };
+
+    let mut mapped = InferredTableSchema {
+        columns: selected
+            .iter()
+            .map(|&i| schema.columns[i].clone())
+            .collect(),
+        source_files: schema.source_files.clone(),
+    };
+
+    for (from, to) in renames {
+        let Some(index) = find_file_column(schema, from) else {
+            bail!(
+                "--map column {from} not found in file columns [{}]",
+                names()
+            );
+        };
+        let Some(position) = selected.iter().position(|&i| i == index) else {
+            bail!("--map column {from} is not among the --columns to import");
+        };
+        mapped.columns[position].ddl_name = quote_identifier(to);
+    }
+
+    for (i, column) in mapped.columns.iter().enumerate() {
+        if mapped.columns[..i]
+            .iter()
+            .any(|c| c.ddl_name.eq_ignore_ascii_case(&column.ddl_name))
+        {
+            bail!(
+                "more than one file column maps to table column {}",
+                column.ddl_name
+            );
+        }
+    }
+
+    let pairs = mapped
+        .columns
+        .iter()
+        .map(|c| (c.original_name.clone(), c.ddl_name.clone()))
+        .collect();
+
+    Ok((mapped, ColumnMapping { selected, pairs }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use exarrow_rs::types::{ExasolType, InferredColumn};
+    use std::path::PathBuf;
+
+    fn schema(names: &[&str]) -> InferredTableSchema {
+        InferredTableSchema {
+            columns: names
+                .iter()
+                .map(|name| InferredColumn {
+                    original_name: name.to_string(),
+                    ddl_name: format!("\"{name}\""),
+                    exasol_type: ExasolType::Double,
+                    nullable: true,
+                })
+                .collect(),
+            source_files: vec![PathBuf::from("data.csv")],
+        }
+    }
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn no_options_keep_every_column() {
+        let (mapped, mapping) = apply(&schema(&["a", "b"]), &[], &[]).unwrap();
+        assert_eq!(mapped.columns.len(), 2);
+        assert!(mapping.keeps_all_columns(2));
+    }
+
+    #[test]
+    fn columns_select_and_reorder() {
+        let (mapped, mapping) =
+            apply(&schema(&["a", "b", "c"]), &strings(&["c", "A"]), &[]).unwrap();
+        assert_eq!(mapping.selected, vec![2, 0]);
+        assert_eq!(mapped.columns[0].ddl_name, "\"c\"");
+        assert_eq!(mapped.columns[1].ddl_name, "\"a\"");
+        assert!(!mapping.keeps_all_columns(3));
+    }
+
+    #[test]
+    fn map_renames_table_column() {
+        let renames = parse_renames(&strings(&["b=customer_id"])).unwrap();
+        let (mapped, mapping) = apply(&schema(&["a", "b"]), &[], &renames).unwrap();
+        assert_eq!(mapped.columns[1].ddl_name, "\"customer_id\"");
+        assert_eq!(
+            mapping.pairs[1],
+            ("b".to_string(), "\"customer_id\"".to_string())
+        );
+        assert!(mapping.keeps_all_columns(2));
+    }
+
+    #[test]
+    fn unknown_column_fails() {
+        let err = apply(&schema(&["a"]), &strings(&["x"]), &[]).unwrap_err();
+        assert!(err.to_string().contains("column x not found"), "got: {err}");
+    }
+
+    #[test]
+    fn map_of_unselected_column_fails() {
+        let renames = parse_renames(&strings(&["b=x"])).unwrap();
+        let err = apply(&schema(&["a", "b"]), &strings(&["a"]), &renames).unwrap_err();
+        assert!(
+            err.to_string().contains("not among the --columns"),
+            "got: {err}"
+        );
+    }
+
+    #[test]
+    fn duplicate_target_columns_fail() {
+        let renames = parse_renames(&strings(&["a=b"])).unwrap();
+        let err = apply(&schema(&["a", "b"]), &[], &renames).unwrap_err();
+        assert!(err.to_string().contains("more than one"), "got: {err}");
+    }
+
+    #[test]
+    fn malformed_map_value_fails() {
+        assert!(parse_renames(&strings(&["a"])).is_err());
+        assert!(parse_renames(&strings(&["=b"])).is_err());
+    }
+
+    #[test]
+    fn map_target_may_contain_commas() {
+        let args = crate::cli::test_upload_args(&["--map", "a=Last, First", "--map", "b=y"]);
+        assert_eq!(
+            parse_renames(&args.map).unwrap(),
+            vec![
+                ("a".to_string(), "Last, First".to_string()),
+                ("b".to_string(), "y".to_string())
+            ]
+        );
+    }
+}
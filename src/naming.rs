@@ -0,0 +1,150 @@
+use exarrow_rs::types::{quote_identifier, sanitize_column_name, InferredTableSchema};
+
+use crate::cli::ColumnNames;
+
+/// Converts a file column name into a table column name under `mode`. The result is
+/// the name as stored in the table, without quotes.
+pub fn table_column_name(name: &str, mode: ColumnNames) -> String {
+    match mode {
+        ColumnNames::Quoted => name.to_string(),
+        ColumnNames::Upper => sanitize_column_name(name),
+        ColumnNames::Snake => snake_case(name),
+    }
+}
+
+/// Splits a name into words at separators, lower-to-upper case changes, and the end of
+/// an uppercase run (`HTTPServer` -> `HTTP_SERVER`), and joins them uppercased with
+/// underscores.
+fn snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut result = String::with_capacity(name.len() + 4);
+
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_ascii_alphanumeric() {
+            if !result.is_empty() && !result.ends_with('_') {
+                result.push('_');
+            }
+            continue;
+        }
+
+        let prev = i.checked_sub(1).map(|p| chars[p]);
+        let next = chars.get(i + 1);
+        let word_start = c.is_ascii_uppercase()
+            && prev.is_some_and(|p| {
+                p.is_ascii_lowercase()
+                    || p.is_ascii_digit()
+                    || (p.is_ascii_uppercase() && next.is_some_and(|n| n.is_ascii_lowercase()))
+            });
+        if word_start && !result.ends_with('_') {
+            result.push('_');
+        }
+        result.push(c.to_ascii_uppercase());
+    }
+
+    let result = result.trim_end_matches('_');
+    if result.is_empty() {
+        "_".to_string()
+    } else if result.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{result}")
+    } else {
+        result.to_string()
+    }
+}
+
+/// Renames the schema's table columns according to `mode`. Names that collide after
+/// conversion, ignoring case, get a numeric suffix (`A_B`, `A_B_2`, ...).
+///
+/// Converted names are still quoted in the DDL, so that words such as `DATE` or
+/// `ORDER` do not clash with reserved keywords; being uppercase, they can be written
+/// without quotes in queries.
+pub fn apply(schema: &mut InferredTableSchema, mode: ColumnNames) {
+    if mode == ColumnNames::Quoted {
+        return;
+    }
+
+    let mut taken: Vec<String> = Vec::with_capacity(schema.columns.len());
+    for column in &mut schema.columns {
+        let base = table_column_name(&column.original_name, mode);
+        let mut name = base.clone();
+        let mut suffix = 2;
+        while taken.iter().any(|t| t.eq_ignore_ascii_case(&name)) {
+            name = format!("{base}_{suffix}");
+            suffix += 1;
+        }
+        column.ddl_name = quote_identifier(&name);
+        taken.push(name);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use exarrow_rs::types::{ExasolType, InferredColumn};
+    use std::path::PathBuf;
+
+    fn schema(names: &[&str]) -> InferredTableSchema {
+        InferredTableSchema {
+            columns: names
+                .iter()
+                .map(|name| InferredColumn {
+                    original_name: name.to_string(),
+                    ddl_name: quote_identifier(name),
+                    exasol_type: ExasolType::Double,
+                    nullable: true,
+                })
+                .collect(),
+            source_files: vec![PathBuf::from("data.csv")],
+        }
+    }
+
+    fn ddl_names(schema: &InferredTableSchema) -> Vec<&str> {
+        schema.columns.iter().map(|c| c.ddl_name.as_str()).collect()
+    }
+
+    #[test]
+    fn snake_case_splits_words() {
+        assert_eq!(snake_case("customerId"), "CUSTOMER_ID");
+        assert_eq!(snake_case("HTTPServer"), "HTTP_SERVER");
+        assert_eq!(snake_case("Order Date"), "ORDER_DATE");
+        assert_eq!(snake_case("total__amount_"), "TOTAL_AMOUNT");
+        assert_eq!(snake_case("address2Line"), "ADDRESS2_LINE");
+        assert_eq!(snake_case("2nd value"), "_2ND_VALUE");
+        assert_eq!(snake_case("%"), "_");
+    }
+
+    #[test]
+    fn upper_sanitizes_names() {
+        assert_eq!(
+            table_column_name("customerId", ColumnNames::Upper),
+            "CUSTOMERID"
+        );
+        assert_eq!(table_column_name("my col", ColumnNames::Upper), "MY_COL");
+    }
+
+    #[test]
+    fn quoted_mode_keeps_names() {
+        let mut schema = schema(&["customerId"]);
+        apply(&mut schema, ColumnNames::Quoted);
+        assert_eq!(ddl_names(&schema), vec!["\"customerId\""]);
+    }
+
+    #[test]
+    fn collisions_get_suffixes() {
+        let mut schema = schema(&["a b", "a_b", "A-B", "a_b_2"]);
+        apply(&mut schema, ColumnNames::Upper);
+        assert_eq!(
+            ddl_names(&schema),
+            vec!["\"A_B\"", "\"A_B_2\"", "\"A_B_3\"", "\"A_B_2_2\""]
+        );
+    }
+
+    #[test]
+    fn snake_mode_renames_columns() {
+        let mut schema = schema(&["customerId", "Order Date"]);
+        apply(&mut schema, ColumnNames::Snake);
+        assert_eq!(
+            ddl_names(&schema),
+            vec!["\"CUSTOMER_ID\"", "\"ORDER_DATE\""]
+        );
+    }
+}
@@ -0,0 +1,432 @@
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{bail, Context, Result};
+use arrow::array::{make_array, Array, ArrayRef, AsArray, StringBuilder};
+use arrow::buffer::NullBuffer;
+use arrow::compute::CastOptions;
+use arrow::datatypes::{DataType, Field, FieldRef, Fields, Schema, SchemaRef};
+use arrow::record_batch::RecordBatch;
+use arrow_json::writer::{make_encoder, EncoderOptions};
+use exarrow_rs::types::{
+    quote_identifier, ExasolType, InferredColumn, InferredTableSchema, TypeMapper,
+};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+use crate::cli::NestedColumns;
+
+/// A table column read out of data with nested fields.
+#[derive(Debug, Clone, PartialEq)]
+struct Column {
+    /// The field name, with the names of enclosing structs for flattened fields
+    /// (`address_city`).
+    name: String,
+    /// The indices of the field and its enclosing fields in the file schema.
+    path: Vec<usize>,
+    /// The type of the values, or `Utf8` for values stored as JSON strings.
+    data_type: DataType,
+    /// Whether the values are structs, lists, or maps, stored as JSON strings.
+    as_json: bool,
+    nullable: bool,
+}
+
+/// How the fields of a file schema with structs, lists, or maps map to flat table
+/// columns, following `--nested`.
+#[derive(Debug, Clone)]
+pub struct NestedLayout {
+    columns: Vec<Column>,
+    /// The schema of the batches [`NestedLayout::apply`] returns.
+    schema: SchemaRef,
+    /// For each nested field of the file, how it is loaded.
+    description: Vec<String>,
+}
+
+impl NestedLayout {
+    /// Lays out `fields` as table columns. Structs are flattened into one column per
+    /// field unless `nested` asks for JSON strings; lists and maps are always stored
+    /// as JSON strings. With [`NestedColumns::Reject`], fails listing the nested
+    /// fields.
+    pub fn new(fields: &Fields, nested: NestedColumns) -> Result<NestedLayout> {
+        if nested == NestedColumns::Reject {
+            let offending: Vec<String> = fields
+                .iter()
+                .filter_map(|f| nested_kind(f.data_type()).map(|kind| (f, kind)))
+                .map(|(f, kind)| format!("{} ({kind})", f.name()))
+                .collect();
+            if !offending.is_empty() {
+                bail!(
+                    "nested columns are not loaded with --nested reject: {}; \
+                     use --nested flatten or --nested json to load them",
+                    offending.join(", ")
+                );
+            }
+        }
+
+        let mut columns = Vec::new();
+        visit(fields, "", &[], false, nested, &mut columns);
+        for (i, column) in columns.iter().enumerate() {
+            if columns[..i].iter().any(|c| c.name == column.name) {
+                bail!(
+                    "flattened column {} clashes with another column of that name; \
+                     use --nested json to load the struct as a JSON string",
+                    column.name
+                );
+            }
+        }
+        let schema = Arc::new(Schema::new(
+            columns
+                .iter()
+                .map(|c| Field::new(&c.name, c.data_type.clone(), true))
+                .collect::<Vec<_>>(),
+        ));
+        let description = fields
+            .iter()
+            .enumerate()
+            .filter_map(|(i, field)| {
+                let kind = nested_kind(field.data_type())?;
+                let loaded: Vec<String> = columns
+                    .iter()
+                    .filter(|c| c.path[0] == i)
+                    .map(|c| {
+                        if c.as_json {
+                            format!("{} as JSON", c.name)
+                        } else {
+                            c.name.clone()
+                        }
+                    })
+                    .collect();
+                Some(format!(
+                    "{} ({kind}) -> {}",
+                    field.name(),
+                    loaded.join(", ")
+                ))
+            })
+            .collect();
+
+        Ok(NestedLayout {
+            columns,
+            schema,
+            description,
+        })
+    }
+
+    /// The table schema the data is loaded into.
+    pub fn table_schema(&self, paths: &[PathBuf]) -> InferredTableSchema {
+        InferredTableSchema {
+            columns: self
+                .columns
+                .iter()
+                .map(|c| InferredColumn {
+                    original_name: c.name.clone(),
+                    ddl_name: quote_identifier(&c.name),
+                    exasol_type: TypeMapper::arrow_to_exasol(&c.data_type)
+                        .unwrap_or(ExasolType::Varchar { size: 2_000_000 }),
+                    nullable: c.nullable,
+                })
+                .collect(),
+            source_files: paths.to_vec(),
+        }
+    }
+
+    /// One line per nested field of the file, naming the columns it is loaded into.
+    pub fn description(&self) -> &[String] {
+        &self.description
+    }
+
+    /// Turns a batch read with the file schema into one with a column per table
+    /// column.
+    pub fn apply(&self, batch: &RecordBatch) -> Result<RecordBatch> {
+        let arrays = self
+            .columns
+            .iter()
+            .map(|column| column_values(batch, column))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(RecordBatch::try_new(Arc::clone(&self.schema), arrays)?)
+    }
+}
+
+/// Adds the columns of `fields` to `columns`; `nullable` is whether an enclosing
+/// struct is.
+fn visit(
+    fields: &[FieldRef],
+    prefix: &str,
+    path: &[usize],
+    nullable: bool,
+    nested: NestedColumns,
+    columns: &mut Vec<Column>,
+) {
+    for (i, field) in fields.iter().enumerate() {
+        let name = format!("{prefix}{}", field.name());
+        let path = [path, &[i]].concat();
+        let nullable = nullable || field.is_nullable();
+        let (data_type, as_json) = match field.data_type() {
+            DataType::Struct(children)
+                if nested == NestedColumns::Flatten && !children.is_empty() =>
+            {
+                visit(
+                    children,
+                    &format!("{name}_"),
+                    &path,
+                    nullable,
+                    nested,
+                    columns,
+                );
+                continue;
+            }
+            data_type if nested_kind(data_type).is_some() => (DataType::Utf8, true),
+            DataType::Null => (DataType::Utf8, false),
+            data_type => (data_type.clone(), false),
+        };
+        columns.push(Column {
+            name,
+            path,
+            data_type,
+            as_json,
+            nullable,
+        });
+    }
+}
+
+/// Whether a field of this type is a struct, list, or map, and which.
+fn nested_kind(data_type: &DataType) -> Option<&'static str> {
+    match data_type {
+        DataType::Struct(_) => Some("struct"),
+        DataType::List(_) | DataType::LargeList(_) | DataType::FixedSizeList(..) => Some("list"),
+        DataType::Map(..) => Some("map"),
+        _ => None,
+    }
+}
+
+/// Whether any of `fields` is a struct, list, or map.
+pub fn has_nested(fields: &Fields) -> bool {
+    fields.iter().any(|f| nested_kind(f.data_type()).is_some())
+}
+
+/// Extracts a column from a batch read with the file schema. A nested field is NULL
+/// wherever one of its enclosing structs is. A value that does not fit the column
+/// type, as in a later file with wider types than the first, is an error rather
+/// than NULL.
+fn column_values(batch: &RecordBatch, column: &Column) -> Result<ArrayRef> {
+    let mut array = Arc::clone(batch.column(column.path[0]));
+    for &i in &column.path[1..] {
+        let parent = array.as_struct();
+        let child = parent.column(i);
+        let nulls = NullBuffer::union(parent.nulls(), child.nulls());
+        array = make_array(child.to_data().into_builder().nulls(nulls).build()?);
+    }
+
+    if column.as_json {
+        json_strings(&array)
+    } else if array.data_type() == &column.data_type {
+        Ok(array)
+    } else {
+        let options = CastOptions {
+            safe: false,
+            ..Default::default()
+        };
+        arrow::compute::cast_with_options(&array, &column.data_type, &options)
+            .with_context(|| format!("column {} does not fit its type", column.name))
+    }
+}
+
+/// Encodes every value of `array` as a JSON string.
+fn json_strings(array: &ArrayRef) -> Result<ArrayRef> {
+    let field: FieldRef = Arc::new(Field::new("value", array.data_type().clone(), true));
+    let options = EncoderOptions::default();
+    let mut encoder = make_encoder(&field, array.as_ref(), &options)?;
+
+    let mut builder = StringBuilder::new();
+    let mut buf = Vec::new();
+    for i in 0..array.len() {
+        if encoder.is_null(i) {
+            builder.append_null();
+            continue;
+        }
+        buf.clear();
+        encoder.encode(i, &mut buf);
+        builder.append_value(String::from_utf8_lossy(&buf));
+    }
+    Ok(Arc::new(builder.finish()))
+}
+
+/// The fields of a Parquet file, read from its footer.
+pub fn parquet_fields(path: &Path) -> Result<Fields> {
+    let file = File::open(path).with_context(|| format!("failed to open {}", path.display()))?;
+    let builder = ParquetRecordBatchReaderBuilder::try_new(file)
+        .with_context(|| format!("failed to read Parquet file {}", path.display()))?;
+    Ok(builder.schema().fields().clone())
+}
+
+/// Reads the batches of a Parquet file with nested fields, one at a time, laid out
+/// as table columns.
+pub fn parquet_batches(
+    path: &Path,
+    layout: &NestedLayout,
+) -> Result<impl Iterator<Item = Result<RecordBatch>>> {
+    let file = File::open(path).with_context(|| format!("failed to open {}", path.display()))?;
+    let reader = ParquetRecordBatchReaderBuilder::try_new(file)?.build()?;
+    let layout = layout.clone();
+    Ok(reader.map(move |batch| layout.apply(&batch?)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Int64Array, ListBuilder, MapBuilder, StringArray, StructArray};
+    use arrow::util::display::array_value_to_string;
+    use parquet::arrow::ArrowWriter;
+
+    /// A batch with a struct, a list, and a map column; the second row is NULL in each.
+    fn nested_batch() -> RecordBatch {
+        let name: ArrayRef = Arc::new(StringArray::from(vec![Some("alice"), Some("bob")]));
+        let customer = StructArray::new(
+            vec![Field::new("name", DataType::Utf8, true)].into(),
+            vec![name],
+            Some(NullBuffer::from(vec![true, false])),
+        );
+
+        let mut tags = ListBuilder::new(StringBuilder::new());
+        tags.values().append_value("a");
+        tags.values().append_value("b");
+        tags.append(true);
+        tags.append(false);
+
+        let mut attributes = MapBuilder::new(None, StringBuilder::new(), StringBuilder::new());
+        attributes.keys().append_value("color");
+        attributes.values().append_value("red");
+        attributes.append(true).unwrap();
+        attributes.append(false).unwrap();
+
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(Int64Array::from(vec![1, 2])),
+            Arc::new(customer),
+            Arc::new(tags.finish()),
+            Arc::new(attributes.finish()),
+        ];
+        let fields: Vec<Field> = ["id", "customer", "tags", "attributes"]
+            .iter()
+            .zip(&columns)
+            .map(|(name, array)| Field::new(*name, array.data_type().clone(), true))
+            .collect();
+        RecordBatch::try_new(Arc::new(Schema::new(fields)), columns).unwrap()
+    }
+
+    fn names(layout: &NestedLayout) -> Vec<&str> {
+        layout.columns.iter().map(|c| c.name.as_str()).collect()
+    }
+
+    fn values(batch: &RecordBatch, column: usize) -> Vec<String> {
+        let array = batch.column(column);
+        (0..batch.num_rows())
+            .map(|row| {
+                if array.is_null(row) {
+                    "NULL".to_string()
+                } else {
+                    array_value_to_string(array, row).unwrap()
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn flattened_names_must_not_clash() {
+        let fields = Fields::from(vec![
+            Field::new(
+                "customer",
+                DataType::Struct(Fields::from(vec![Field::new("name", DataType::Utf8, true)])),
+                true,
+            ),
+            Field::new("customer_name", DataType::Utf8, true),
+        ]);
+        let error = NestedLayout::new(&fields, NestedColumns::Flatten).unwrap_err();
+        assert!(error.to_string().contains("customer_name"), "got: {error}");
+        assert!(NestedLayout::new(&fields, NestedColumns::Json).is_ok());
+    }
+
+    #[test]
+    fn structs_are_flattened_and_lists_and_maps_kept_as_json() {
+        let batch = nested_batch();
+        let layout = NestedLayout::new(batch.schema().fields(), NestedColumns::Flatten).unwrap();
+        assert_eq!(
+            names(&layout),
+            vec!["id", "customer_name", "tags", "attributes"]
+        );
+        assert_eq!(
+            layout.description(),
+            vec![
+                "customer (struct) -> customer_name",
+                "tags (list) -> tags as JSON",
+                "attributes (map) -> attributes as JSON",
+            ]
+        );
+
+        let batch = layout.apply(&batch).unwrap();
+        assert_eq!(values(&batch, 1), vec!["alice", "NULL"]);
+        assert_eq!(values(&batch, 2), vec![r#"["a","b"]"#, "NULL"]);
+        assert_eq!(values(&batch, 3), vec![r#"{"color":"red"}"#, "NULL"]);
+    }
+
+    #[test]
+    fn structs_can_be_kept_as_json() {
+        let batch = nested_batch();
+        let layout = NestedLayout::new(batch.schema().fields(), NestedColumns::Json).unwrap();
+        assert_eq!(names(&layout), vec!["id", "customer", "tags", "attributes"]);
+
+        let batch = layout.apply(&batch).unwrap();
+        assert_eq!(values(&batch, 1), vec![r#"{"name":"alice"}"#, "NULL"]);
+    }
+
+    #[test]
+    fn reject_lists_the_nested_columns() {
+        let batch = nested_batch();
+        let err = NestedLayout::new(batch.schema().fields(), NestedColumns::Reject).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("customer (struct), tags (list), attributes (map)"),
+            "got: {err}"
+        );
+
+        let flat = Fields::from(vec![Field::new("id", DataType::Int64, false)]);
+        assert!(NestedLayout::new(&flat, NestedColumns::Reject).is_ok());
+    }
+
+    #[test]
+    fn values_too_wide_for_the_layout_fail() {
+        let batch = nested_batch();
+        let mut fields: Vec<FieldRef> = batch.schema().fields().iter().cloned().collect();
+        fields[0] = Arc::new(Field::new("id", DataType::Int32, true));
+        let layout = NestedLayout::new(&fields.into(), NestedColumns::Flatten).unwrap();
+        assert_eq!(values(&layout.apply(&batch).unwrap(), 0), vec!["1", "2"]);
+
+        let mut columns = batch.columns().to_vec();
+        columns[0] = Arc::new(Int64Array::from(vec![1, i64::MAX]));
+        let wide = RecordBatch::try_new(batch.schema(), columns).unwrap();
+        let err = layout.apply(&wide).unwrap_err();
+        assert!(format!("{err:#}").contains("column id"), "got: {err:#}");
+    }
+
+    #[test]
+    fn parquet_files_are_read_through_the_layout() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested.parquet");
+        let batch = nested_batch();
+        let mut writer =
+            ArrowWriter::try_new(File::create(&path).unwrap(), batch.schema(), None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let fields = parquet_fields(&path).unwrap();
+        assert!(has_nested(&fields));
+        let layout = NestedLayout::new(&fields, NestedColumns::Flatten).unwrap();
+        let schema = layout.table_schema(std::slice::from_ref(&path));
+        assert_eq!(schema.columns[1].ddl_name, "\"customer_name\"");
+
+        let batches: Vec<_> = parquet_batches(&path, &layout)
+            .unwrap()
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(values(&batches[0], 1), vec!["alice", "NULL"]);
+        assert_eq!(values(&batches[0], 3), vec![r#"{"color":"red"}"#, "NULL"]);
+    }
+}
@@ -0,0 +1,158 @@
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use exarrow_rs::types::{ExasolType, InferredTableSchema};
+
+use crate::table::parse_column_type;
+
+/// Parse `--type` values of the form `column=TYPE`, e.g. `zip=VARCHAR(10)`.
+pub fn parse_type_overrides(values: &[String]) -> Result<Vec<(String, ExasolType)>> {
+    values
+        .iter()
+        .map(|value| {
+            let Some((column, ty)) = value.split_once('=') else {
+                bail!("invalid --type value '{value}', expected COLUMN=TYPE");
+            };
+            Ok((column.trim().to_string(), parse_type(column.trim(), ty)?))
+        })
+        .collect()
+}
+
+/// Read a schema file with one column definition per line, such as
+/// `zip VARCHAR(10)` or `"Order Date" DATE`. Blank lines, lines starting with `--`
+/// or `#`, and trailing commas are ignored.
+pub fn read_schema_file(path: &Path) -> Result<Vec<(String, ExasolType)>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read schema file {}", path.display()))?;
+    parse_schema_definitions(&content)
+        .with_context(|| format!("invalid schema file {}", path.display()))
+}
+
+fn parse_schema_definitions(content: &str) -> Result<Vec<(String, ExasolType)>> {
+    let mut definitions = Vec::new();
+
+    for (number, line) in content.lines().enumerate() {
+        let line = line.trim().trim_end_matches(',').trim();
+        if line.is_empty() || line.starts_with("--") || line.starts_with('#') {
+            continue;
+        }
+
+        let (column, ty) = match line.strip_prefix('"') {
+            Some(rest) => rest
+                .split_once('"')
+                .map(|(column, ty)| (column.to_string(), ty)),
+            None => line
+                .split_once(char::is_whitespace)
+                .map(|(column, ty)| (column.to_string(), ty)),
+        }
+        .with_context(|| format!("line {}: expected a column name and a type", number + 1))?;
+
+        let ty = parse_type(&column, ty).with_context(|| format!("line {}", number + 1))?;
+        definitions.push((column, ty));
+    }
+
+    Ok(definitions)
+}
+
+fn parse_type(column: &str, ty: &str) -> Result<ExasolType> {
+    parse_column_type(ty)
+        .with_context(|| format!("unsupported type '{}' for column {column}", ty.trim()))
+}
+
+/// Replaces the inferred types of the given columns, looked up by file column name.
+/// Later overrides win, so `--type` values are applied after the schema file.
+pub fn apply(schema: &mut InferredTableSchema, overrides: &[(String, ExasolType)]) -> Result<()> {
+    for (column, ty) in overrides {
+        let Some(index) = crate::mapping::find_file_column(schema, column) else {
+            bail!("type override for column {column}, which is not among the columns to load");
+        };
+        schema.columns[index].exasol_type = ty.clone();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use exarrow_rs::types::InferredColumn;
+    use std::path::PathBuf;
+
+    fn schema(names: &[&str]) -> InferredTableSchema {
+        InferredTableSchema {
+            columns: names
+                .iter()
+                .map(|name| InferredColumn {
+                    original_name: name.to_string(),
+                    ddl_name: format!("\"{name}\""),
+                    exasol_type: ExasolType::Decimal {
+                        precision: 36,
+                        scale: 0,
+                    },
+                    nullable: true,
+                })
+                .collect(),
+            source_files: vec![PathBuf::from("data.csv")],
+        }
+    }
+
+    #[test]
+    fn type_override_is_parsed() {
+        let overrides = parse_type_overrides(&["zip=VARCHAR(10)".to_string()]).unwrap();
+        assert_eq!(
+            overrides,
+            vec![("zip".to_string(), ExasolType::Varchar { size: 10 })]
+        );
+    }
+
+    #[test]
+    fn unsupported_type_fails() {
+        let err = parse_type_overrides(&["zip=TEXT".to_string()]).unwrap_err();
+        assert!(
+            err.to_string().contains("unsupported type 'TEXT'"),
+            "got: {err}"
+        );
+    }
+
+    #[test]
+    fn schema_definitions_skip_comments_and_commas() {
+        let definitions = parse_schema_definitions(
+            "-- overrides\nzip VARCHAR(10),\n\n\"Order Date\" DATE\n# flags\nactive BOOLEAN\n",
+        )
+        .unwrap();
+        assert_eq!(
+            definitions,
+            vec![
+                ("zip".to_string(), ExasolType::Varchar { size: 10 }),
+                ("Order Date".to_string(), ExasolType::Date),
+                ("active".to_string(), ExasolType::Boolean),
+            ]
+        );
+    }
+
+    #[test]
+    fn schema_definition_without_type_fails() {
+        let err = parse_schema_definitions("zip\n").unwrap_err();
+        assert!(err.to_string().contains("line 1"), "got: {err}");
+    }
+
+    #[test]
+    fn apply_patches_matching_column() {
+        let mut schema = schema(&["id", "zip"]);
+        apply(
+            &mut schema,
+            &[("ZIP".to_string(), ExasolType::Varchar { size: 10 })],
+        )
+        .unwrap();
+        assert_eq!(
+            schema.columns[1].exasol_type,
+            ExasolType::Varchar { size: 10 }
+        );
+    }
+
+    #[test]
+    fn apply_rejects_unknown_column() {
+        let mut schema = schema(&["id"]);
+        let err = apply(&mut schema, &[("zip".to_string(), ExasolType::Date)]).unwrap_err();
+        assert!(err.to_string().contains("column zip"), "got: {err}");
+    }
+}
@@ -0,0 +1,174 @@
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{bail, Result};
+use exarrow_rs::types::{ColumnNameMode, CsvInferenceOptions};
+
+use crate::lineage::{AddedColumn, AddedValue};
+
+/// The value Hive writes for a partition whose key is NULL.
+const HIVE_DEFAULT_PARTITION: &str = "__HIVE_DEFAULT_PARTITION__";
+
+/// The Hive-style `key=value` directories in the path of a file, outermost first,
+/// with the values percent-decoded. A key that occurs twice takes the inner value.
+pub fn partition_values(path: &Path) -> Vec<(String, String)> {
+    let mut values: Vec<(String, String)> = Vec::new();
+    let directories = path.parent().into_iter().flat_map(Path::components);
+    for component in directories {
+        let Component::Normal(segment) = component else {
+            continue;
+        };
+        let segment = segment.to_string_lossy();
+        let Some((key, value)) = segment.split_once('=') else {
+            continue;
+        };
+        if key.is_empty() {
+            continue;
+        }
+        let value = percent_decode(value);
+        match values.iter_mut().find(|(k, _)| k == key) {
+            Some(entry) => entry.1 = value,
+            None => values.push((key.to_string(), value)),
+        }
+    }
+    values
+}
+
+/// The value of the partition `key` in the path of a file; empty, which Exasol loads
+/// as NULL, if the path has no such directory or it holds Hive's NULL partition.
+pub fn partition_value(path: &Path, key: &str) -> String {
+    partition_values(path)
+        .into_iter()
+        .find(|(k, _)| k == key)
+        .map(|(_, value)| value)
+        .filter(|value| value != HIVE_DEFAULT_PARTITION)
+        .unwrap_or_default()
+}
+
+/// Finds the partition keys in the paths of `paths`, in the order they first occur,
+/// and infers the type of each from its values the way CSV column types are inferred.
+pub fn partition_columns(paths: &[PathBuf]) -> Result<Vec<AddedColumn>> {
+    let mut keys: Vec<String> = Vec::new();
+    for path in paths {
+        for (key, _) in partition_values(path) {
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+    }
+    if keys.is_empty() {
+        bail!("--partition-columns found no key=value directories in the paths of the files");
+    }
+
+    // The values of each file form one CSV row, so that the types are inferred by the
+    // same rules as the columns of a CSV file.
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(&keys)?;
+    for path in paths {
+        writer.write_record(keys.iter().map(|key| partition_value(path, key)))?;
+    }
+    let values = writer.into_inner()?;
+    let options = CsvInferenceOptions::new()
+        .with_has_header(true)
+        .with_column_name_mode(ColumnNameMode::Quoted);
+    let schema = crate::inference::infer_csv_schema_from(
+        values.as_slice(),
+        Path::new("partition directories"),
+        &options,
+    )?;
+
+    Ok(keys
+        .into_iter()
+        .zip(schema.columns)
+        .map(|(key, column)| AddedColumn {
+            name: key.clone(),
+            value: AddedValue::Partition {
+                key,
+                exasol_type: column.exasol_type,
+            },
+        })
+        .collect())
+}
+
+/// Decodes `%XX` escapes, as Hive writes characters such as `:` and `/` in partition
+/// values. Anything that is not a valid escape is kept as it is.
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| value.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use exarrow_rs::types::ExasolType;
+
+    #[test]
+    fn key_value_directories_are_parsed() {
+        let path = Path::new("events/dt=2026-10-01/region=eu/part-0.parquet");
+        assert_eq!(
+            partition_values(path),
+            vec![
+                ("dt".to_string(), "2026-10-01".to_string()),
+                ("region".to_string(), "eu".to_string())
+            ]
+        );
+        assert_eq!(partition_value(path, "region"), "eu");
+        assert_eq!(partition_value(path, "hour"), "");
+    }
+
+    #[test]
+    fn file_names_and_default_partitions_are_not_values() {
+        let path = Path::new("events/region=__HIVE_DEFAULT_PARTITION__/a=b.csv");
+        assert_eq!(partition_values(path).len(), 1);
+        assert_eq!(partition_value(path, "region"), "");
+    }
+
+    #[test]
+    fn values_are_percent_decoded() {
+        assert_eq!(percent_decode("10%3A00%3A00"), "10:00:00");
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(percent_decode("%zz"), "%zz");
+    }
+
+    #[test]
+    fn types_are_inferred_across_the_files() {
+        let paths = [
+            PathBuf::from("events/dt=2026-10-01/region=eu/hour=5/a.parquet"),
+            PathBuf::from("events/dt=2026-10-02/region=us/hour=17/b.parquet"),
+        ];
+        let columns = partition_columns(&paths).unwrap();
+        let types: Vec<(String, ExasolType)> = columns
+            .into_iter()
+            .map(|c| match c.value {
+                AddedValue::Partition { key, exasol_type } => (key, exasol_type),
+                other => panic!("unexpected value {other:?}"),
+            })
+            .collect();
+        assert_eq!(types[0], ("dt".into(), ExasolType::Date));
+        assert!(matches!(types[1].1, ExasolType::Varchar { .. }));
+        assert!(matches!(types[2].1, ExasolType::Decimal { scale: 0, .. }));
+    }
+
+    #[test]
+    fn paths_without_partitions_are_refused() {
+        let error = partition_columns(&[PathBuf::from("events/a.parquet")]).unwrap_err();
+        assert!(error.to_string().contains("no key=value directories"));
+    }
+}
@@ -0,0 +1,359 @@
+use std::io::{self, IsTerminal, Read, Write};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::compression::ByteCounter;
+
+/// How often the progress bar is redrawn on a terminal.
+const BAR_INTERVAL: Duration = Duration::from_millis(250);
+
+/// How often a progress line is written when stderr is not a terminal.
+const LINE_INTERVAL: Duration = Duration::from_secs(10);
+
+const BAR_WIDTH: usize = 30;
+
+/// Reports how far an upload or export has got on stderr while it runs: a progress
+/// bar on a terminal, otherwise a `progress` line of `key=value` pairs every ten
+/// seconds. Reporting stops when the `Progress` is dropped.
+pub struct Progress {
+    meter: Meter,
+    reporter: tokio::task::JoinHandle<()>,
+}
+
+impl Progress {
+    /// Starts reporting. `total_bytes` is the size of the input, when it is known.
+    pub fn start(total_bytes: Option<u64>) -> Progress {
+        let meter = Meter {
+            counters: Arc::new(Counters {
+                total_bytes,
+                terminal: io::stderr().is_terminal(),
+                ..Counters::default()
+            }),
+        };
+
+        let counters = Arc::clone(&meter.counters);
+        let reporter = tokio::spawn(async move {
+            let started = Instant::now();
+            let period = if counters.terminal {
+                BAR_INTERVAL
+            } else {
+                LINE_INTERVAL
+            };
+            let mut ticks = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+            loop {
+                ticks.tick().await;
+                let snapshot = counters.snapshot(started.elapsed());
+                let mut stderr = io::stderr().lock();
+                if counters.terminal {
+                    let _ = write!(stderr, "\r{}\x1b[K", snapshot.bar());
+                } else {
+                    let _ = writeln!(stderr, "{}", snapshot.line());
+                }
+                let _ = stderr.flush();
+            }
+        });
+
+        Progress { meter, reporter }
+    }
+
+    /// A handle to count the work done through.
+    pub fn meter(&self) -> Meter {
+        self.meter.clone()
+    }
+
+    /// Stops reporting and removes the progress bar.
+    pub fn finish(self) {}
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        self.reporter.abort();
+        if self.meter.counters.terminal {
+            eprint!("\r\x1b[K");
+        }
+    }
+}
+
+#[derive(Default)]
+struct Counters {
+    total_bytes: Option<u64>,
+    terminal: bool,
+    bytes: AtomicU64,
+    rows: AtomicU64,
+    /// The input files being read, whose bytes are counted as they are read.
+    sources: Mutex<Vec<ByteCounter>>,
+}
+
+impl Counters {
+    fn snapshot(&self, elapsed: Duration) -> Snapshot {
+        let source_bytes: u64 = self
+            .sources
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|counter| counter.counts().compressed)
+            .sum();
+        Snapshot {
+            elapsed,
+            bytes: self.bytes.load(Ordering::Relaxed) + source_bytes,
+            total_bytes: self.total_bytes,
+            rows: self.rows.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Counts the bytes and rows of an upload or export for its [`Progress`]. Rows are
+/// counted as they pass for CSV streams, and once loaded for other data.
+#[derive(Clone)]
+pub struct Meter {
+    counters: Arc<Counters>,
+}
+
+impl Meter {
+    pub fn add_bytes(&self, bytes: u64) {
+        self.counters.bytes.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    pub fn add_rows(&self, rows: u64) {
+        self.counters.rows.fetch_add(rows, Ordering::Relaxed);
+    }
+
+    /// Counts the file bytes of an input file as they are read.
+    pub fn watch(&self, counter: ByteCounter) {
+        self.counters.sources.lock().unwrap().push(counter);
+    }
+
+    /// Counts the bytes read through `reader`.
+    pub fn bytes<R: Read>(&self, reader: R) -> MeteredReader<R> {
+        MeteredReader {
+            inner: reader,
+            meter: self.clone(),
+            count_rows: false,
+            skip_lines: 0,
+        }
+    }
+
+    /// Counts the lines of CSV data read through `reader` as rows, after the first
+    /// `skip_lines` (a header); see [`Meter::count_lines`] for how exact that is.
+    pub fn rows<R: Read>(&self, reader: R, skip_lines: u64) -> MeteredReader<R> {
+        MeteredReader {
+            inner: reader,
+            meter: self.clone(),
+            count_rows: true,
+            skip_lines,
+        }
+    }
+
+    /// Prints a line to stderr without mixing it into the progress bar.
+    pub fn message(&self, message: &str) {
+        let mut stderr = io::stderr().lock();
+        if self.counters.terminal {
+            let _ = write!(stderr, "\r\x1b[K");
+        }
+        let _ = writeln!(stderr, "{message}");
+    }
+
+    /// Counts the newlines in `data` as rows, skipping the first `skip_lines`. This is
+    /// an approximate row count for progress only: a quoted CSV field that spans lines
+    /// counts once per line.
+    fn count_lines(&self, data: &[u8], skip_lines: &mut u64) {
+        let mut lines = data.iter().filter(|&&b| b == b'\n').count() as u64;
+        let skipped = lines.min(*skip_lines);
+        *skip_lines -= skipped;
+        lines -= skipped;
+        self.add_rows(lines);
+    }
+}
+
+pub struct MeteredReader<R> {
+    inner: R,
+    meter: Meter,
+    count_rows: bool,
+    skip_lines: u64,
+}
+
+impl<R: Read> Read for MeteredReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        if self.count_rows {
+            self.meter.count_lines(&buf[..n], &mut self.skip_lines);
+        } else {
+            self.meter.add_bytes(n as u64);
+        }
+        Ok(n)
+    }
+}
+
+/// The state of an upload or export at one point in time.
+struct Snapshot {
+    elapsed: Duration,
+    bytes: u64,
+    total_bytes: Option<u64>,
+    rows: u64,
+}
+
+impl Snapshot {
+    fn rows_per_second(&self) -> u64 {
+        let seconds = self.elapsed.as_secs_f64();
+        if seconds > 0.0 {
+            (self.rows as f64 / seconds) as u64
+        } else {
+            0
+        }
+    }
+
+    /// The time left at the rate the bytes have been read so far.
+    fn eta(&self) -> Option<Duration> {
+        let total = self.total_bytes?;
+        if self.bytes == 0 {
+            return None;
+        }
+        let left = total.saturating_sub(self.bytes) as f64;
+        Some(self.elapsed.mul_f64(left / self.bytes as f64))
+    }
+
+    /// The progress bar, e.g.
+    /// `[=========>          ]  45% 1.2 GB / 2.7 GB, 3000000 rows, 152000 rows/s, ETA 3m12s`.
+    fn bar(&self) -> String {
+        let rate = format!("{} rows, {} rows/s", self.rows, self.rows_per_second());
+        let Some(total) = self.total_bytes.filter(|&t| t > 0) else {
+            return format!(
+                "{}, {rate}, {} elapsed",
+                format_bytes(self.bytes),
+                format_duration(self.elapsed)
+            );
+        };
+
+        let fraction = (self.bytes as f64 / total as f64).min(1.0);
+        let filled = (fraction * BAR_WIDTH as f64) as usize;
+        let bar = match filled {
+            BAR_WIDTH => "=".repeat(BAR_WIDTH),
+            _ => format!(
+                "{}>{}",
+                "=".repeat(filled),
+                " ".repeat(BAR_WIDTH - filled - 1)
+            ),
+        };
+        let eta = self.eta().map_or_else(|| "-".to_string(), format_duration);
+        format!(
+            "[{bar}] {:>3}% {} / {}, {rate}, ETA {eta}",
+            (fraction * 100.0) as u32,
+            format_bytes(self.bytes),
+            format_bytes(total)
+        )
+    }
+
+    /// The machine-readable progress line, e.g.
+    /// `progress elapsed_s=60 bytes=1048576 total_bytes=4194304 rows=5000 rows_per_s=83 eta_s=180`.
+    fn line(&self) -> String {
+        let mut line = format!(
+            "progress elapsed_s={} bytes={}",
+            self.elapsed.as_secs(),
+            self.bytes
+        );
+        if let Some(total) = self.total_bytes {
+            line.push_str(&format!(" total_bytes={total}"));
+        }
+        line.push_str(&format!(
+            " rows={} rows_per_s={}",
+            self.rows,
+            self.rows_per_second()
+        ));
+        if let Some(eta) = self.eta() {
+            line.push_str(&format!(" eta_s={}", eta.as_secs()));
+        }
+        line
+    }
+}
+
+/// Formats a byte count with base-10 units, like the sizes `--max-file-size` accepts.
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [(u64, &str); 3] = [(1_000_000_000, "GB"), (1_000_000, "MB"), (1_000, "KB")];
+    for (size, unit) in UNITS {
+        if bytes >= size {
+            return format!("{:.1} {unit}", bytes as f64 / size as f64);
+        }
+    }
+    format!("{bytes} B")
+}
+
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    match (seconds / 3600, seconds / 60 % 60, seconds % 60) {
+        (0, 0, s) => format!("{s}s"),
+        (0, m, s) => format!("{m}m{s:02}s"),
+        (h, m, _) => format!("{h}h{m:02}m"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(bytes: u64, total_bytes: Option<u64>) -> Snapshot {
+        Snapshot {
+            elapsed: Duration::from_secs(60),
+            bytes,
+            total_bytes,
+            rows: 6000,
+        }
+    }
+
+    fn meter() -> Meter {
+        Meter {
+            counters: Arc::new(Counters::default()),
+        }
+    }
+
+    #[test]
+    fn bar_shows_share_rate_and_eta() {
+        let bar = snapshot(1_000_000, Some(4_000_000)).bar();
+        assert_eq!(
+            bar,
+            format!(
+                "[=======>{}]  25% 1.0 MB / 4.0 MB, 6000 rows, 100 rows/s, ETA 3m00s",
+                " ".repeat(22)
+            )
+        );
+    }
+
+    #[test]
+    fn bar_without_total_shows_elapsed_time() {
+        let bar = snapshot(1_500, None).bar();
+        assert_eq!(bar, "1.5 KB, 6000 rows, 100 rows/s, 1m00s elapsed");
+    }
+
+    #[test]
+    fn line_is_key_value_pairs() {
+        assert_eq!(
+            snapshot(1_000_000, Some(4_000_000)).line(),
+            "progress elapsed_s=60 bytes=1000000 total_bytes=4000000 rows=6000 rows_per_s=100 eta_s=180"
+        );
+        assert_eq!(
+            snapshot(0, None).line(),
+            "progress elapsed_s=60 bytes=0 rows=6000 rows_per_s=100"
+        );
+    }
+
+    #[test]
+    fn durations_are_compact() {
+        assert_eq!(format_duration(Duration::from_secs(42)), "42s");
+        assert_eq!(
+            format_duration(Duration::from_secs(3 * 3600 + 5 * 60 + 9)),
+            "3h05m"
+        );
+    }
+
+    #[test]
+    fn readers_count_bytes_and_rows() {
+        let meter = meter();
+        let data = b"id,name\n1,a\n2,b\n3,c\n";
+        io::copy(&mut meter.rows(&data[..], 1), &mut io::sink()).unwrap();
+        io::copy(&mut meter.bytes(&data[..]), &mut io::sink()).unwrap();
+
+        let snapshot = meter.counters.snapshot(Duration::from_secs(1));
+        assert_eq!(snapshot.rows, 3);
+        assert_eq!(snapshot.bytes, data.len() as u64);
+    }
+}
@@ -0,0 +1,225 @@
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+use exarrow_rs::import::arrow::{CsvWriterOptions, SyncArrowToCsvWriter};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::arrow::ProjectionMask;
+
+use crate::cli::UploadArgs;
+
+/// Reads the CSV columns at `selected`, in that order, out of CSV data with
+/// `column_count` columns, one batch at a time as the returned iterator advances.
+///
+/// Every column is read as a string, so that values reach Exasol exactly as they are
+/// written in the file and are parsed by the import just like an unprojected upload.
+pub fn csv_column_batches(
+    reader: impl Read,
+    args: &UploadArgs,
+    column_count: usize,
+    selected: &[usize],
+) -> Result<impl Iterator<Item = Result<RecordBatch>>> {
+    let fields: Vec<Field> = (0..column_count)
+        .map(|i| Field::new(format!("c{i}"), DataType::Utf8, true))
+        .collect();
+    let (sorted, order) = projection_order(selected);
+
+    let mut builder = arrow_csv::ReaderBuilder::new(Arc::new(Schema::new(fields)))
+        .with_header(!args.no_header)
+        .with_delimiter(args.delimiter as u8)
+        .with_quote(args.quote as u8)
+        .with_projection(sorted);
+    if let Some(escape) = args.escape {
+        builder = builder.with_escape(escape as u8);
+    }
+    if !args.null_value.is_empty() {
+        builder = builder.with_null_regex(regex::Regex::new(&format!(
+            "^{}$",
+            regex::escape(&args.null_value)
+        ))?);
+    }
+
+    Ok(builder
+        .build(reader)?
+        .map(move |batch| Ok(batch?.project(&order)?)))
+}
+
+/// Reads the top-level Parquet columns at `selected`, in that order, one batch at a
+/// time as the returned iterator advances.
+pub fn parquet_column_batches(
+    path: &Path,
+    selected: &[usize],
+) -> Result<impl Iterator<Item = Result<RecordBatch>>> {
+    let file = File::open(path).with_context(|| format!("failed to open {}", path.display()))?;
+    let builder = ParquetRecordBatchReaderBuilder::try_new(file)?;
+    let (sorted, order) = projection_order(selected);
+    let mask = ProjectionMask::roots(builder.parquet_schema(), sorted);
+
+    Ok(builder
+        .with_projection(mask)
+        .build()?
+        .map(move |batch| Ok(batch?.project(&order)?)))
+}
+
+/// Hands out record batches as CSV rows for Exasol's import, encoding one batch at a
+/// time as the rows are read, so that a stream of batches never has to be held in
+/// memory.
+pub struct CsvRows<I> {
+    batches: I,
+    selected: Option<Vec<usize>>,
+    values: Vec<String>,
+    buf: Vec<u8>,
+    position: usize,
+}
+
+impl<I: Iterator<Item = Result<RecordBatch>>> CsvRows<I> {
+    /// Encodes the columns at `selected`, in that order, or all columns if `None`.
+    pub fn new(batches: I, selected: Option<Vec<usize>>) -> CsvRows<I> {
+        CsvRows {
+            batches,
+            selected,
+            values: Vec::new(),
+            buf: Vec::new(),
+            position: 0,
+        }
+    }
+
+    /// Appends `values` to every row, after the encoded columns (`--add-column`).
+    pub fn with_values(mut self, values: Vec<String>) -> CsvRows<I> {
+        self.values = values;
+        self
+    }
+
+    /// Encodes the next batch into the buffer; returns `false` once all batches are
+    /// read.
+    fn fill(&mut self) -> Result<bool> {
+        let Some(batch) = self.batches.next() else {
+            return Ok(false);
+        };
+        let mut batch = batch?;
+        if let Some(selected) = &self.selected {
+            batch = batch.project(selected)?;
+        }
+        let batch = crate::lineage::append_values(&batch, &self.values)?;
+
+        self.buf.clear();
+        self.position = 0;
+        SyncArrowToCsvWriter::new(&mut self.buf, CsvWriterOptions::default())
+            .write_batch(&batch)?;
+        Ok(true)
+    }
+}
+
+impl<I: Iterator<Item = Result<RecordBatch>>> Read for CsvRows<I> {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        while self.position == self.buf.len() {
+            if !self.fill().map_err(io::Error::other)? {
+                return Ok(0);
+            }
+        }
+
+        let n = out.len().min(self.buf.len() - self.position);
+        out[..n].copy_from_slice(&self.buf[self.position..self.position + n]);
+        self.position += n;
+        Ok(n)
+    }
+}
+
+/// Readers return projected columns in file order. Returns the sorted column indices
+/// to read and, for each selected column, its position among them.
+fn projection_order(selected: &[usize]) -> (Vec<usize>, Vec<usize>) {
+    let mut sorted = selected.to_vec();
+    sorted.sort_unstable();
+    let order = selected
+        .iter()
+        .map(|i| sorted.binary_search(i).unwrap_or_default())
+        .collect();
+    (sorted, order)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::test_upload_args;
+    use arrow::array::{Array, StringArray};
+
+    fn column(batch: &RecordBatch, i: usize) -> Vec<Option<String>> {
+        let array = batch
+            .column(i)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        (0..array.len())
+            .map(|row| (!array.is_null(row)).then(|| array.value(row).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn projection_order_maps_selection_to_sorted_positions() {
+        assert_eq!(projection_order(&[2, 0]), (vec![0, 2], vec![1, 0]));
+    }
+
+    #[test]
+    fn csv_columns_are_selected_and_reordered() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.csv");
+        std::fs::write(&path, "id,name,zip\n1,alice,01234\n2,,99999\n").unwrap();
+
+        let batches: Vec<RecordBatch> = csv_column_batches(
+            File::open(&path).unwrap(),
+            &test_upload_args(&[]),
+            3,
+            &[2, 1],
+        )
+        .unwrap()
+        .collect::<Result<_>>()
+        .unwrap();
+        assert_eq!(batches[0].num_columns(), 2);
+        assert_eq!(
+            column(&batches[0], 0),
+            vec![Some("01234".into()), Some("99999".into())]
+        );
+        assert_eq!(column(&batches[0], 1), vec![Some("alice".into()), None]);
+    }
+
+    #[test]
+    fn csv_custom_null_value_becomes_null() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.csv");
+        std::fs::write(&path, "id;name\n1;NULL\n").unwrap();
+
+        let args = test_upload_args(&["--delimiter", ";", "--null-value", "NULL"]);
+        let batches: Vec<RecordBatch> =
+            csv_column_batches(File::open(&path).unwrap(), &args, 2, &[1])
+                .unwrap()
+                .collect::<Result<_>>()
+                .unwrap();
+        assert_eq!(column(&batches[0], 0), vec![None]);
+    }
+
+    #[test]
+    fn added_values_fill_rows_of_every_batch() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.csv");
+        let content: String = std::iter::once("id\n".to_string())
+            .chain((0..3000).map(|i| format!("{i}\n")))
+            .collect();
+        std::fs::write(&path, content).unwrap();
+
+        let batches =
+            csv_column_batches(File::open(&path).unwrap(), &test_upload_args(&[]), 1, &[0])
+                .unwrap();
+        let mut rows = CsvRows::new(batches, None).with_values(vec!["a.csv".into()]);
+        let mut csv = String::new();
+        rows.read_to_string(&mut csv).unwrap();
+
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3000);
+        assert_eq!(lines[0], "0,a.csv");
+        assert_eq!(lines[2999], "2999,a.csv");
+    }
+}
@@ -0,0 +1,520 @@
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Context, Result};
+use exarrow_rs::types::ExasolType;
+
+use crate::cli::UploadArgs;
+
+/// Whether CSV rows are checked client-side, so that bad rows are rejected instead of
+/// failing the upload.
+pub fn enabled(args: &UploadArgs) -> bool {
+    args.max_errors.is_some() || args.reject_file.is_some()
+}
+
+fn csv_reader<R: Read>(source: R, args: &UploadArgs) -> csv::Reader<R> {
+    csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .delimiter(args.delimiter as u8)
+        .quote(args.quote as u8)
+        .escape(args.escape.map(|c| c as u8))
+        .from_reader(source)
+}
+
+/// Passes on the rows of CSV data whose number of fields matches the first row's, so
+/// that schema inference is not stopped by rows that the import will reject. Rows are
+/// read as they are needed, so inference from a sample stops early as before.
+pub struct WellFormedRows<R: Read> {
+    reader: csv::Reader<R>,
+    field_count: Option<usize>,
+    record: csv::ByteRecord,
+    delimiter: u8,
+    quote: u8,
+    buf: Vec<u8>,
+    position: usize,
+}
+
+impl<R: Read> WellFormedRows<R> {
+    pub fn new(source: R, args: &UploadArgs) -> WellFormedRows<R> {
+        WellFormedRows {
+            reader: csv_reader(source, args),
+            field_count: None,
+            record: csv::ByteRecord::new(),
+            delimiter: args.delimiter as u8,
+            quote: args.quote as u8,
+            buf: Vec::new(),
+            position: 0,
+        }
+    }
+
+    fn fill(&mut self) -> io::Result<bool> {
+        loop {
+            if !self.reader.read_byte_record(&mut self.record)? {
+                return Ok(false);
+            }
+            if *self.field_count.get_or_insert(self.record.len()) != self.record.len() {
+                continue;
+            }
+
+            self.buf.clear();
+            self.position = 0;
+            let mut writer = csv::WriterBuilder::new()
+                .delimiter(self.delimiter)
+                .quote(self.quote)
+                .from_writer(&mut self.buf);
+            writer.write_byte_record(&self.record)?;
+            writer.flush()?;
+            return Ok(true);
+        }
+    }
+}
+
+impl<R: Read> Read for WellFormedRows<R> {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        while self.position == self.buf.len() {
+            if !self.fill()? {
+                return Ok(0);
+            }
+        }
+        Ok(drain(&self.buf, &mut self.position, out))
+    }
+}
+
+/// Copies as much of `buf` from `position` on into `out` as fits.
+fn drain(buf: &[u8], position: &mut usize, out: &mut [u8]) -> usize {
+    let n = out.len().min(buf.len() - *position);
+    out[..n].copy_from_slice(&buf[*position..*position + n]);
+    *position += n;
+    n
+}
+
+/// The rows rejected across all files of an upload: where they are written and how
+/// many of them are tolerated.
+pub struct Rejects {
+    writer: Option<Mutex<csv::Writer<File>>>,
+    count: AtomicU64,
+    max: Option<u64>,
+}
+
+impl Rejects {
+    /// Creates the reject file, if one is given, with a header row of the position
+    /// and reason columns followed by the file columns.
+    pub fn new(args: &UploadArgs, file_columns: &[String]) -> Result<Rejects> {
+        let writer = match &args.reject_file {
+            Some(path) => {
+                let mut writer = csv::WriterBuilder::new()
+                    .flexible(true)
+                    .from_path(path)
+                    .with_context(|| format!("failed to create {}", path.display()))?;
+                let header = ["file", "line", "error"]
+                    .into_iter()
+                    .chain(file_columns.iter().map(String::as_str));
+                writer.write_record(header)?;
+                Some(Mutex::new(writer))
+            }
+            None => None,
+        };
+        Ok(Rejects {
+            writer,
+            count: AtomicU64::new(0),
+            max: args.max_errors,
+        })
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count.load(Ordering::SeqCst)
+    }
+
+    /// Records a rejected row: to the reject file if there is one, otherwise to stderr.
+    fn reject(
+        &self,
+        path: &Path,
+        line: u64,
+        error: &str,
+        record: &csv::ByteRecord,
+    ) -> io::Result<()> {
+        match &self.writer {
+            Some(writer) => {
+                let mut writer = writer.lock().unwrap();
+                let position = [path.display().to_string(), line.to_string(), error.into()];
+                let fields = position.iter().map(|s| s.as_bytes()).chain(record.iter());
+                writer.write_record(fields)?;
+                writer.flush()?;
+            }
+            None => eprintln!("Rejected {} line {line}: {error}", path.display()),
+        }
+
+        self.count.fetch_add(1, Ordering::SeqCst);
+        self.check_limit().map_err(io::Error::other)
+    }
+
+    /// Fails once more rows were rejected than `--max-errors` allows.
+    pub fn check_limit(&self) -> Result<()> {
+        match self.max {
+            Some(max) if self.count() > max => {
+                anyhow::bail!("more than {max} rows rejected (--max-errors {max})")
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// How the rows of a CSV file are checked: the number of fields each row must have,
+/// and the file columns that are loaded with the types and names they are checked
+/// against.
+pub struct RowCheck {
+    pub field_count: usize,
+    pub selected: Vec<usize>,
+    pub types: Vec<ExasolType>,
+    pub names: Vec<String>,
+    pub has_header: bool,
+    pub null_value: String,
+}
+
+impl RowCheck {
+    /// Returns why `record` cannot be loaded, if it cannot.
+    fn problem(&self, record: &csv::ByteRecord) -> Option<String> {
+        if record.len() != self.field_count {
+            return Some(format!(
+                "expected {} fields, found {}",
+                self.field_count,
+                record.len()
+            ));
+        }
+
+        for ((&i, ty), name) in self.selected.iter().zip(&self.types).zip(&self.names) {
+            let Ok(value) = std::str::from_utf8(&record[i]) else {
+                return Some(format!("column {name:?}: invalid UTF-8"));
+            };
+            if value.is_empty() || value == self.null_value {
+                continue;
+            }
+            if let Err(problem) = check_value(value, ty) {
+                return Some(format!("column {name:?}: {problem}"));
+            }
+        }
+        None
+    }
+}
+
+/// Reads CSV rows, rejects those that cannot be loaded, and hands out the others as
+/// CSV in Exasol's default format, with only the selected columns and NULLs as empty
+/// fields.
+pub struct CheckedRows<R: Read> {
+    reader: csv::Reader<R>,
+    path: PathBuf,
+    check: Arc<RowCheck>,
+    rejects: Arc<Rejects>,
+    record: csv::ByteRecord,
+    skip_header: bool,
+    values: Vec<String>,
+    buf: Vec<u8>,
+    position: usize,
+}
+
+impl<R: Read> CheckedRows<R> {
+    pub fn new(
+        source: R,
+        path: &Path,
+        args: &UploadArgs,
+        check: Arc<RowCheck>,
+        rejects: Arc<Rejects>,
+    ) -> CheckedRows<R> {
+        CheckedRows {
+            reader: csv_reader(source, args),
+            path: path.to_path_buf(),
+            skip_header: check.has_header,
+            check,
+            rejects,
+            record: csv::ByteRecord::new(),
+            values: Vec::new(),
+            buf: Vec::new(),
+            position: 0,
+        }
+    }
+
+    /// Appends `values` to every row that is handed out (`--add-column`).
+    pub fn with_values(mut self, values: Vec<String>) -> CheckedRows<R> {
+        self.values = values;
+        self
+    }
+
+    /// Reads rows until one can be loaded and encodes it into the buffer; returns
+    /// `false` at the end of the data.
+    fn fill(&mut self) -> io::Result<bool> {
+        loop {
+            if !self.reader.read_byte_record(&mut self.record)? {
+                return Ok(false);
+            }
+            if std::mem::take(&mut self.skip_header) {
+                continue;
+            }
+
+            let line = self.record.position().map_or(0, |p| p.line());
+            if let Some(problem) = self.check.problem(&self.record) {
+                self.rejects
+                    .reject(&self.path, line, &problem, &self.record)?;
+                continue;
+            }
+
+            self.buf.clear();
+            self.position = 0;
+            let mut writer = csv::Writer::from_writer(&mut self.buf);
+            // NULLs are passed on as empty fields, which Exasol reads as NULL.
+            let null_value = self.check.null_value.as_bytes();
+            let fields = self.check.selected.iter().map(|&i| match &self.record[i] {
+                field if field == null_value => &[][..],
+                field => field,
+            });
+            let fields = fields.chain(self.values.iter().map(String::as_bytes));
+            writer.write_record(fields)?;
+            writer.flush()?;
+            return Ok(true);
+        }
+    }
+}
+
+impl<R: Read> Read for CheckedRows<R> {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        while self.position == self.buf.len() {
+            if !self.fill()? {
+                return Ok(0);
+            }
+        }
+
+        Ok(drain(&self.buf, &mut self.position, out))
+    }
+}
+
+/// Checks that a non-NULL CSV value converts to `ty` the way Exasol's import converts
+/// it. Types exapump cannot check are left to Exasol.
+fn check_value(value: &str, ty: &ExasolType) -> Result<(), String> {
+    let valid = match ty {
+        ExasolType::Boolean => matches!(
+            value.to_ascii_uppercase().as_str(),
+            "TRUE" | "FALSE" | "T" | "F" | "Y" | "N" | "YES" | "NO" | "ON" | "OFF" | "1" | "0"
+        ),
+        ExasolType::Char { size } | ExasolType::Varchar { size } => {
+            let len = value.chars().count();
+            if len > *size {
+                return Err(format!(
+                    "value of {len} characters is too long for {}",
+                    ty.to_ddl_type()
+                ));
+            }
+            true
+        }
+        ExasolType::Decimal { precision, scale } => match integer_digits(value) {
+            Some(digits) if digits > i64::from(*precision) - i64::from(*scale) => {
+                return Err(format!(
+                    "value {value:?} is out of range for {}",
+                    ty.to_ddl_type()
+                ));
+            }
+            Some(_) => true,
+            None => false,
+        },
+        ExasolType::Double => value.trim().parse::<f64>().is_ok_and(f64::is_finite),
+        ExasolType::Date => is_date(value),
+        ExasolType::Timestamp { .. } => is_timestamp(value),
+        _ => true,
+    };
+
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("invalid {} value {value:?}", ty.to_ddl_type()))
+    }
+}
+
+/// The number of digits before the decimal point of a number such as `-12.5` or
+/// `1.5E3`, ignoring leading zeros; `None` if `value` is not a number.
+fn integer_digits(value: &str) -> Option<i64> {
+    let unsigned = value.trim().trim_start_matches(['+', '-']);
+    let (mantissa, exponent) = match unsigned.split_once(['e', 'E']) {
+        Some((m, e)) => (m, e.parse::<i64>().ok()?),
+        None => (unsigned, 0),
+    };
+    let (int, frac) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let all_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    if int.len() + frac.len() == 0 || !all_digits(int) || !all_digits(frac) {
+        return None;
+    }
+
+    let digits = format!("{int}{frac}");
+    let Some(first) = digits.find(|c| c != '0') else {
+        return Some(0);
+    };
+    Some((int.len() as i64 + exponent - first as i64).max(0))
+}
+
+fn is_date(value: &str) -> bool {
+    let parts: Vec<&str> = value.split('-').collect();
+    let [year, month, day] = parts.as_slice() else {
+        return false;
+    };
+    if year.len() != 4 || month.len() != 2 || day.len() != 2 {
+        return false;
+    }
+    let (Ok(year), Ok(month), Ok(day)) = (
+        year.parse::<u32>(),
+        month.parse::<u32>(),
+        day.parse::<u32>(),
+    ) else {
+        return false;
+    };
+
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return false,
+    };
+    (1..=days).contains(&day) && year >= 1
+}
+
+/// Accepts `YYYY-MM-DD HH:MI:SS` with optional fractional seconds, or a date alone.
+fn is_timestamp(value: &str) -> bool {
+    let Some((date, time)) = value.split_once([' ', 'T']) else {
+        return is_date(value);
+    };
+    let (time, fraction) = time.split_once('.').unwrap_or((time, "0"));
+    let parts: Vec<&str> = time.split(':').collect();
+    let [hour, minute, second] = parts.as_slice() else {
+        return false;
+    };
+    let field = |s: &str, max: u32| s.len() == 2 && s.parse::<u32>().is_ok_and(|v| v <= max);
+
+    is_date(date)
+        && field(hour, 23)
+        && field(minute, 59)
+        && field(second, 59)
+        && !fraction.is_empty()
+        && fraction.len() <= 9
+        && fraction.bytes().all(|b| b.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::test_upload_args;
+
+    fn check() -> Arc<RowCheck> {
+        Arc::new(RowCheck {
+            field_count: 3,
+            selected: vec![0, 2],
+            types: vec![
+                ExasolType::Decimal {
+                    precision: 4,
+                    scale: 0,
+                },
+                ExasolType::Date,
+            ],
+            names: vec!["id".into(), "day".into()],
+            has_header: true,
+            null_value: String::new(),
+        })
+    }
+
+    #[test]
+    fn values_are_checked_against_types() {
+        let decimal = ExasolType::Decimal {
+            precision: 5,
+            scale: 2,
+        };
+        assert!(check_value("123.456", &decimal).is_ok());
+        assert!(check_value("-0.5e2", &decimal).is_ok());
+        assert!(check_value("1234", &decimal).is_err());
+        assert!(check_value("12a", &decimal).is_err());
+        assert!(check_value("yes", &ExasolType::Boolean).is_ok());
+        assert!(check_value("maybe", &ExasolType::Boolean).is_err());
+        assert!(check_value("NaN", &ExasolType::Double).is_err());
+        assert!(check_value("2024-02-29", &ExasolType::Date).is_ok());
+        assert!(check_value("2023-02-29", &ExasolType::Date).is_err());
+        let timestamp = ExasolType::Timestamp {
+            with_local_time_zone: false,
+        };
+        assert!(check_value("2024-01-31 23:59:59.123456", &timestamp).is_ok());
+        assert!(check_value("2024-01-31 24:00:00", &timestamp).is_err());
+        let err = check_value("abcd", &ExasolType::Varchar { size: 3 }).unwrap_err();
+        assert!(err.contains("too long"), "got: {err}");
+    }
+
+    #[test]
+    fn integer_digits_follow_the_exponent() {
+        assert_eq!(integer_digits("00012.50"), Some(2));
+        assert_eq!(integer_digits("1.5E3"), Some(4));
+        assert_eq!(integer_digits("0.001"), Some(0));
+        assert_eq!(integer_digits("."), None);
+    }
+
+    #[test]
+    fn bad_rows_are_rejected_and_good_rows_passed_on() {
+        let dir = tempfile::tempdir().unwrap();
+        let reject_path = dir.path().join("rejects.csv");
+        let reject_file = reject_path.to_str().unwrap();
+        let args = test_upload_args(&["--reject-file", reject_file]);
+        let columns = ["id".to_string(), "name".into(), "day".into()];
+        let rejects = Arc::new(Rejects::new(&args, &columns).unwrap());
+
+        let data = "id,name,day\n1,a,2024-01-01\nx,b,2024-01-02\n3,c\n4,\"d, e\",\n";
+        let mut rows = CheckedRows::new(
+            data.as_bytes(),
+            Path::new("data.csv"),
+            &args,
+            check(),
+            Arc::clone(&rejects),
+        );
+        let mut content = String::new();
+        rows.read_to_string(&mut content).unwrap();
+
+        assert_eq!(content, "1,2024-01-01\n4,\n");
+        assert_eq!(rejects.count(), 2);
+
+        drop(rejects);
+        let rejected = std::fs::read_to_string(&reject_path).unwrap();
+        assert_eq!(
+            rejected,
+            "file,line,error,id,name,day\n\
+             data.csv,3,\"column \"\"id\"\": invalid DECIMAL(4,0) value \"\"x\"\"\",x,b,2024-01-02\n\
+             data.csv,4,\"expected 3 fields, found 2\",3,c\n"
+        );
+    }
+
+    #[test]
+    fn too_many_rejects_fail_the_read() {
+        let args = test_upload_args(&["--max-errors", "1"]);
+        let rejects = Arc::new(Rejects::new(&args, &[]).unwrap());
+
+        let data = "id,name,day\nx,a,\ny,b,\n";
+        let mut rows = CheckedRows::new(
+            data.as_bytes(),
+            Path::new("data.csv"),
+            &args,
+            check(),
+            rejects,
+        );
+        let err = rows.read_to_end(&mut Vec::new()).unwrap_err();
+        assert!(
+            err.to_string().contains("more than 1 rows rejected"),
+            "got: {err}"
+        );
+    }
+
+    #[test]
+    fn malformed_rows_are_dropped_for_inference() {
+        let args = test_upload_args(&[]);
+        let data = "id,name\n1,a\n2\n3,c,extra\n4,d\n";
+        let mut rows = String::new();
+        WellFormedRows::new(data.as_bytes(), &args)
+            .read_to_string(&mut rows)
+            .unwrap();
+        assert_eq!(rows, "id,name\n1,a\n4,d\n");
+    }
+}
@@ -0,0 +1,194 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use crate::cli::ReportFormat;
+
+/// A structured record of one run of a command, for orchestration tools that would
+/// otherwise scrape the human-readable output. Commands fill in what applies to them;
+/// fields that do not apply are left out of the JSON.
+#[derive(Serialize, Default)]
+pub struct Report {
+    pub command: &'static str,
+    pub status: Status,
+    pub duration_ms: u64,
+    /// Where the data came from, for exports: the table or the query.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// Where the data went: the table for uploads, the output file for exports.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<FileReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rows: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rejected_rows: Option<u64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub statements: Vec<StatementReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<ErrorReport>,
+}
+
+#[derive(Serialize, Default, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    #[default]
+    Succeeded,
+    Failed,
+    /// Not run because an earlier file or statement failed.
+    NotStarted,
+    /// Not loaded because an earlier upload already loaded it (`--resume`,
+    /// `--audit-table`).
+    Skipped,
+}
+
+/// What happened to one input or output file.
+#[derive(Serialize)]
+pub struct FileReport {
+    pub path: PathBuf,
+    pub status: Status,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rows: Option<u64>,
+    /// The size of the file on disk.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl FileReport {
+    pub fn new(path: &Path, status: Status) -> FileReport {
+        FileReport {
+            path: path.to_path_buf(),
+            status,
+            rows: None,
+            bytes: std::fs::metadata(path).ok().map(|m| m.len()),
+            error: None,
+        }
+    }
+}
+
+/// What happened to one statement of a `sql` run.
+#[derive(Serialize)]
+pub struct StatementReport {
+    pub sql: String,
+    pub status: Status,
+    /// The rows returned by a query or affected by DML.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rows: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// The error a run failed with: its message and the causes behind it, outermost first.
+#[derive(Serialize)]
+pub struct ErrorReport {
+    pub message: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub causes: Vec<String>,
+}
+
+impl Report {
+    pub fn new(command: &'static str) -> Report {
+        Report {
+            command,
+            ..Report::default()
+        }
+    }
+
+    /// Records how the run ended and how long it took.
+    pub fn finish<T>(&mut self, result: &Result<T>, duration: Duration) {
+        self.duration_ms = duration.as_millis() as u64;
+        if let Err(e) = result {
+            self.status = Status::Failed;
+            self.error = Some(ErrorReport {
+                message: e.to_string(),
+                causes: e.chain().skip(1).map(|cause| cause.to_string()).collect(),
+            });
+        }
+    }
+
+    /// Writes the report as a single line of JSON to `file`, or to stderr.
+    pub fn emit(&self, format: ReportFormat, file: Option<&Path>) -> Result<()> {
+        let ReportFormat::Json = format;
+        let json = serde_json::to_string(self)?;
+        match file {
+            Some(path) => std::fs::write(path, format!("{json}\n"))
+                .with_context(|| format!("failed to write report to {}", path.display())),
+            None => {
+                eprintln!("{json}");
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fields_that_do_not_apply_are_left_out() {
+        let mut report = Report::new("upload");
+        report.target = Some("s.t".into());
+        report.rows = Some(3);
+        report.finish(&Ok(()), Duration::from_millis(1500));
+
+        let json = serde_json::to_string(&report).unwrap();
+        assert_eq!(
+            json,
+            r#"{"command":"upload","status":"succeeded","duration_ms":1500,"target":"s.t","rows":3}"#
+        );
+    }
+
+    #[test]
+    fn failure_records_the_error_chain() {
+        let mut report = Report::new("sql");
+        let result: Result<()> =
+            Err(anyhow::anyhow!("connection refused").context("failed to connect"));
+        report.finish(&result, Duration::ZERO);
+
+        let json: serde_json::Value = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["status"], "failed");
+        assert_eq!(json["error"]["message"], "failed to connect");
+        assert_eq!(json["error"]["causes"][0], "connection refused");
+    }
+
+    #[test]
+    fn statements_and_files_are_listed() {
+        let mut report = Report::new("sql");
+        report.statements.push(StatementReport {
+            sql: "SELECT 1".into(),
+            status: Status::Succeeded,
+            rows: Some(1),
+            error: None,
+        });
+        report.files.push(FileReport::new(
+            Path::new("missing.csv"),
+            Status::NotStarted,
+        ));
+
+        let json: serde_json::Value = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["statements"][0]["rows"], 1);
+        assert_eq!(json["files"][0]["status"], "not_started");
+        assert!(json["files"][0].get("bytes").is_none());
+    }
+
+    #[test]
+    fn report_file_holds_one_json_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("report.json");
+        Report::new("export")
+            .emit(ReportFormat::Json, Some(&path))
+            .unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(content.lines().count(), 1);
+        assert!(content.starts_with(r#"{"command":"export""#));
+    }
+}
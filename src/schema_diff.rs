@@ -0,0 +1,458 @@
+use std::fmt;
+
+use exarrow_rs::types::{ExasolType, InferredTableSchema};
+
+use crate::table::{normalize_identifier, TableColumn};
+
+/// A difference between the columns inferred from the input files and the columns of
+/// an existing target table.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnDiff {
+    /// The table has a column the files do not provide.
+    Missing { name: String, table_type: String },
+    /// The files provide a column the table does not have.
+    Extra {
+        name: String,
+        ddl_name: String,
+        file_type: ExasolType,
+    },
+    /// The column exists on both sides, but at a different position. Rows are
+    /// imported by position, so values would land in the wrong column.
+    Moved {
+        name: String,
+        file_position: usize,
+        table_position: usize,
+    },
+    /// The table column is of the same kind as the inferred file type, but cannot
+    /// hold every value of it.
+    Narrower {
+        name: String,
+        /// The column name as stored in the table.
+        column: String,
+        file_type: ExasolType,
+        table_type: String,
+    },
+    /// The values of the inferred file type do not convert to the table type at all,
+    /// such as a BOOLEAN column loaded into a DECIMAL one.
+    TypeMismatch {
+        name: String,
+        file_type: ExasolType,
+        table_type: String,
+    },
+}
+
+impl ColumnDiff {
+    /// Whether the import is bound to fail or to load values into the wrong column.
+    /// Narrower types only fail if an actual value does not fit. When the files are
+    /// imported `by_name`, column order does not matter and table columns the files
+    /// lack are filled with their default value.
+    pub fn is_incompatible(&self, by_name: bool) -> bool {
+        match self {
+            ColumnDiff::Extra { .. } | ColumnDiff::TypeMismatch { .. } => true,
+            ColumnDiff::Missing { .. } | ColumnDiff::Moved { .. } => !by_name,
+            ColumnDiff::Narrower { .. } => false,
+        }
+    }
+}
+
+impl fmt::Display for ColumnDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColumnDiff::Missing { name, table_type } => {
+                write!(f, "- {name} {table_type}: in table, missing from file")
+            }
+            ColumnDiff::Extra {
+                name, file_type, ..
+            } => write!(
+                f,
+                "+ {name} {}: in file, missing from table",
+                file_type.to_ddl_type()
+            ),
+            ColumnDiff::Moved {
+                name,
+                file_position,
+                table_position,
+            } => write!(
+                f,
+                "! {name}: column {file_position} in file, column {table_position} in table"
+            ),
+            ColumnDiff::Narrower {
+                name,
+                file_type,
+                table_type,
+                ..
+            } => write!(
+                f,
+                "~ {name}: file type {} is wider than table type {table_type}",
+                file_type.to_ddl_type()
+            ),
+            ColumnDiff::TypeMismatch {
+                name,
+                file_type,
+                table_type,
+            } => write!(
+                f,
+                "x {name}: file type {} cannot be loaded into table type {table_type}",
+                file_type.to_ddl_type()
+            ),
+        }
+    }
+}
+
+/// Compares the inferred schema with the columns of the existing table.
+///
+/// Columns are matched by name, exactly as stored in the table or else
+/// case-insensitively, so that a file column `id` matches a table column `ID`. A
+/// narrower table type is not reported for unsized file types, which would flag
+/// nearly every string and integer column of a CSV file.
+pub fn diff_columns(schema: &InferredTableSchema, table: &[TableColumn]) -> Vec<ColumnDiff> {
+    let mut diffs = Vec::new();
+    let mut matched = Vec::new();
+
+    for (file_index, column) in schema.columns.iter().enumerate() {
+        match find_column(table, &column.ddl_name) {
+            Some(table_index) => {
+                matched.push((file_index, table_index));
+                let table_column = &table[table_index];
+                if let Some(table_type) = table_column.exasol_type() {
+                    let file_type = &column.exasol_type;
+                    if !is_unsized(file_type) && !fits(file_type, &table_type) {
+                        diffs.push(if converts(file_type, &table_type) {
+                            ColumnDiff::Narrower {
+                                name: column.original_name.clone(),
+                                column: table_column.name.clone(),
+                                file_type: file_type.clone(),
+                                table_type: table_column.column_type.clone(),
+                            }
+                        } else {
+                            ColumnDiff::TypeMismatch {
+                                name: column.original_name.clone(),
+                                file_type: file_type.clone(),
+                                table_type: table_column.column_type.clone(),
+                            }
+                        });
+                    }
+                }
+            }
+            None => diffs.push(ColumnDiff::Extra {
+                name: column.original_name.clone(),
+                ddl_name: column.ddl_name.clone(),
+                file_type: column.exasol_type.clone(),
+            }),
+        }
+    }
+
+    for (table_index, column) in table.iter().enumerate() {
+        if !matched.iter().any(|&(_, t)| t == table_index) {
+            diffs.push(ColumnDiff::Missing {
+                name: column.name.clone(),
+                table_type: column.column_type.clone(),
+            });
+        }
+    }
+
+    // Compare the order of the shared columns: the n-th shared file column must be
+    // the n-th shared table column.
+    let mut table_order: Vec<usize> = matched.iter().map(|&(_, t)| t).collect();
+    table_order.sort_unstable();
+    for (&(file_index, table_index), &expected) in matched.iter().zip(&table_order) {
+        if table_index != expected {
+            diffs.push(ColumnDiff::Moved {
+                name: schema.columns[file_index].original_name.clone(),
+                file_position: file_index + 1,
+                table_position: table_index + 1,
+            });
+        }
+    }
+
+    diffs
+}
+
+/// Finds the table column for the inferred column with DDL name `ddl_name`, matching
+/// the name exactly as stored in the table or else case-insensitively.
+pub fn find_column(table: &[TableColumn], ddl_name: &str) -> Option<usize> {
+    let name = normalize_identifier(ddl_name);
+    table.iter().position(|c| c.name == name).or_else(|| {
+        table
+            .iter()
+            .position(|c| c.name.eq_ignore_ascii_case(&name))
+    })
+}
+
+/// The type a `table` column must be widened to so that it holds every value of the
+/// `file` type, for string and decimal columns. Other types cannot be widened, and
+/// neither can a column whose file type is unsized, since nothing is known about the
+/// values that would need the room.
+pub fn widened_type(file: &ExasolType, table: &ExasolType) -> Option<ExasolType> {
+    use ExasolType::*;
+
+    match (file, table) {
+        _ if is_unsized(file) => None,
+        (Char { size: f } | Varchar { size: f }, Char { size: t } | Varchar { size: t }) => {
+            Some(Varchar {
+                size: (*f).max(*t).min(2_000_000),
+            })
+        }
+        (
+            Decimal {
+                precision: fp,
+                scale: fs,
+            },
+            Decimal {
+                precision: tp,
+                scale: ts,
+            },
+        ) => {
+            let scale = (*fs).max(*ts);
+            let integer_digits =
+                (i16::from(*fp) - i16::from(*fs)).max(i16::from(*tp) - i16::from(*ts));
+            let precision = (integer_digits + i16::from(scale)).min(36);
+            Some(Decimal {
+                precision: u8::try_from(precision).ok()?,
+                scale,
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Whether `file` is the catch-all type inferred for any string or integer column,
+/// `VARCHAR(2000000)` or `DECIMAL(36,0)`, which says nothing about how long or how
+/// large the values actually are.
+pub fn is_unsized(file: &ExasolType) -> bool {
+    matches!(
+        file,
+        ExasolType::Varchar { size: 2_000_000 }
+            | ExasolType::Decimal {
+                precision: 36,
+                scale: 0
+            }
+    )
+}
+
+/// Whether values of type `file` convert to type `table` at all, if perhaps not all
+/// of them fit: any value loads into a string column, numbers into numbers, and dates
+/// and timestamps into each other. Other types only convert to their own kind.
+fn converts(file: &ExasolType, table: &ExasolType) -> bool {
+    use ExasolType::*;
+
+    match (file, table) {
+        (_, Char { .. } | Varchar { .. }) => true,
+        (Decimal { .. } | Double, Decimal { .. } | Double) => true,
+        (Date | Timestamp { .. }, Date | Timestamp { .. }) => true,
+        _ => std::mem::discriminant(file) == std::mem::discriminant(table),
+    }
+}
+
+/// Whether a column of type `table` can hold every value of type `file`.
+pub fn fits(file: &ExasolType, table: &ExasolType) -> bool {
+    use ExasolType::*;
+
+    match (file, table) {
+        _ if file == table => true,
+        (
+            Decimal {
+                precision: fp,
+                scale: fs,
+            },
+            Decimal {
+                precision: tp,
+                scale: ts,
+            },
+        ) => ts >= fs && i16::from(*tp) - i16::from(*ts) >= i16::from(*fp) - i16::from(*fs),
+        (Decimal { .. }, Double) => true,
+        (Char { size: f } | Varchar { size: f }, Varchar { size: t }) => f <= t,
+        (Char { size: f }, Char { size: t }) => f <= t,
+        (Date, Timestamp { .. }) | (Timestamp { .. }, Timestamp { .. }) => true,
+        (IntervalDayToSecond { precision: f }, IntervalDayToSecond { precision: t }) => f <= t,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use exarrow_rs::types::InferredColumn;
+    use std::path::PathBuf;
+
+    fn schema(columns: &[(&str, ExasolType)]) -> InferredTableSchema {
+        InferredTableSchema {
+            columns: columns
+                .iter()
+                .map(|(name, ty)| InferredColumn {
+                    original_name: name.to_string(),
+                    ddl_name: format!("\"{name}\""),
+                    exasol_type: ty.clone(),
+                    nullable: true,
+                })
+                .collect(),
+            source_files: vec![PathBuf::from("data.csv")],
+        }
+    }
+
+    fn table(columns: &[(&str, &str)]) -> Vec<TableColumn> {
+        columns
+            .iter()
+            .map(|(name, ty)| TableColumn {
+                name: name.to_string(),
+                column_type: ty.to_string(),
+            })
+            .collect()
+    }
+
+    fn decimal(precision: u8, scale: i8) -> ExasolType {
+        ExasolType::Decimal { precision, scale }
+    }
+
+    #[test]
+    fn identical_columns_have_no_diff() {
+        let file = schema(&[
+            ("id", decimal(18, 0)),
+            ("name", ExasolType::Varchar { size: 100 }),
+        ]);
+        let target = table(&[("id", "DECIMAL(18,0)"), ("name", "VARCHAR(100) UTF8")]);
+        assert!(diff_columns(&file, &target).is_empty());
+    }
+
+    #[test]
+    fn names_match_case_insensitively() {
+        let file = schema(&[("id", decimal(18, 0))]);
+        let target = table(&[("ID", "DECIMAL(18,0)")]);
+        assert!(diff_columns(&file, &target).is_empty());
+    }
+
+    #[test]
+    fn reports_missing_and_extra_columns() {
+        let file = schema(&[
+            ("id", decimal(18, 0)),
+            ("email", ExasolType::Varchar { size: 10 }),
+        ]);
+        let target = table(&[("id", "DECIMAL(18,0)"), ("name", "VARCHAR(100) UTF8")]);
+
+        let diffs = diff_columns(&file, &target);
+        assert_eq!(diffs.len(), 2, "got: {diffs:?}");
+        assert!(matches!(&diffs[0], ColumnDiff::Extra { name, .. } if name == "email"));
+        assert!(matches!(&diffs[1], ColumnDiff::Missing { name, .. } if name == "name"));
+        assert!(diffs.iter().all(|d| d.is_incompatible(false)));
+        assert!(!diffs[1].is_incompatible(true));
+    }
+
+    #[test]
+    fn reports_narrower_table_type_as_compatible() {
+        let file = schema(&[("id", decimal(30, 0))]);
+        let target = table(&[("ID", "DECIMAL(18,0)")]);
+
+        let diffs = diff_columns(&file, &target);
+        assert_eq!(diffs.len(), 1);
+        assert!(matches!(&diffs[0], ColumnDiff::Narrower { .. }));
+        assert!(!diffs[0].is_incompatible(false));
+    }
+
+    #[test]
+    fn reports_type_mismatch_as_incompatible() {
+        let file = schema(&[("active", ExasolType::Boolean)]);
+        let target = table(&[("ACTIVE", "DECIMAL(18,0)")]);
+
+        let diffs = diff_columns(&file, &target);
+        assert_eq!(diffs.len(), 1, "got: {diffs:?}");
+        assert!(matches!(&diffs[0], ColumnDiff::TypeMismatch { .. }));
+        assert!(diffs[0].is_incompatible(true));
+        assert_eq!(
+            diffs[0].to_string(),
+            "x active: file type BOOLEAN cannot be loaded into table type DECIMAL(18,0)"
+        );
+    }
+
+    #[test]
+    fn unsized_file_types_are_not_reported_as_narrower() {
+        let file = schema(&[
+            ("id", decimal(36, 0)),
+            ("name", ExasolType::Varchar { size: 2_000_000 }),
+        ]);
+        let target = table(&[("ID", "DECIMAL(18,0)"), ("NAME", "VARCHAR(20) UTF8")]);
+        assert!(diff_columns(&file, &target).is_empty());
+    }
+
+    #[test]
+    fn reports_moved_columns() {
+        let file = schema(&[("a", ExasolType::Double), ("b", ExasolType::Double)]);
+        let target = table(&[("b", "DOUBLE"), ("a", "DOUBLE")]);
+
+        let diffs = diff_columns(&file, &target);
+        assert_eq!(diffs.len(), 2, "got: {diffs:?}");
+        assert!(diffs.iter().all(|d| matches!(d, ColumnDiff::Moved { .. })));
+    }
+
+    #[test]
+    fn decimal_fits_when_integer_and_fraction_digits_fit() {
+        assert!(fits(&decimal(10, 2), &decimal(12, 2)));
+        assert!(fits(&decimal(10, 2), &decimal(11, 3)));
+        assert!(!fits(&decimal(10, 2), &decimal(10, 3)));
+        assert!(!fits(&decimal(10, 2), &decimal(10, 1)));
+    }
+
+    #[test]
+    fn strings_fit_into_longer_varchar() {
+        assert!(fits(
+            &ExasolType::Varchar { size: 10 },
+            &ExasolType::Varchar { size: 20 }
+        ));
+        assert!(fits(
+            &ExasolType::Char { size: 10 },
+            &ExasolType::Varchar { size: 10 }
+        ));
+        assert!(!fits(
+            &ExasolType::Varchar { size: 20 },
+            &ExasolType::Varchar { size: 10 }
+        ));
+        assert!(!fits(&ExasolType::Varchar { size: 1 }, &ExasolType::Double));
+    }
+
+    #[test]
+    fn decimal_widening_keeps_integer_digits() {
+        assert_eq!(
+            widened_type(&decimal(10, 4), &decimal(18, 0)),
+            Some(decimal(22, 4))
+        );
+        assert_eq!(
+            widened_type(&decimal(30, 0), &decimal(18, 2)),
+            Some(decimal(32, 2))
+        );
+    }
+
+    #[test]
+    fn unsized_file_types_are_not_widened_to() {
+        assert_eq!(widened_type(&decimal(36, 0), &decimal(18, 0)), None);
+        assert_eq!(
+            widened_type(
+                &ExasolType::Varchar { size: 2_000_000 },
+                &ExasolType::Varchar { size: 20 }
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn strings_widen_to_longer_varchar() {
+        assert_eq!(
+            widened_type(
+                &ExasolType::Varchar { size: 500 },
+                &ExasolType::Char { size: 10 }
+            ),
+            Some(ExasolType::Varchar { size: 500 })
+        );
+        assert_eq!(widened_type(&ExasolType::Double, &decimal(18, 0)), None);
+    }
+
+    #[test]
+    fn display_marks_each_kind() {
+        let missing = ColumnDiff::Missing {
+            name: "name".into(),
+            table_type: "VARCHAR(100) UTF8".into(),
+        };
+        assert_eq!(
+            missing.to_string(),
+            "- name VARCHAR(100) UTF8: in table, missing from file"
+        );
+    }
+}
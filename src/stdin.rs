@@ -0,0 +1,100 @@
+use std::io::{self, Cursor, Read};
+
+use anyhow::Result;
+
+/// The input name that stands for stdin.
+pub const STDIN: &str = "-";
+
+/// The name stdin goes by in messages.
+pub const STDIN_NAME: &str = "<stdin>";
+
+/// The number of rows or records of stdin that column types are inferred from, unless
+/// `--infer-rows` says otherwise. The stream cannot be scanned in full before it is
+/// loaded.
+pub const DEFAULT_SAMPLE_ROWS: usize = 10_000;
+
+/// A stream whose first bytes were read ahead, for schema inference, and are kept so
+/// that they can be replayed ahead of the rest of the stream. Only these bytes are
+/// held in memory.
+pub struct Spooled<R> {
+    consumed: Vec<u8>,
+    rest: R,
+}
+
+/// Passes reads through to `inner` and keeps a copy of every byte read.
+struct Recording<'a, R> {
+    inner: &'a mut R,
+    recorded: &'a mut Vec<u8>,
+}
+
+impl<R: Read> Read for Recording<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.recorded.extend_from_slice(&buf[..n]);
+        Ok(n)
+    }
+}
+
+impl<R: Read> Spooled<R> {
+    /// Runs `inspect` on the start of `stream` and spools the bytes it reads.
+    pub fn inspect<T>(
+        mut stream: R,
+        inspect: impl FnOnce(&mut dyn Read) -> Result<T>,
+    ) -> Result<(T, Spooled<R>)> {
+        let mut consumed = Vec::new();
+        let result = inspect(&mut Recording {
+            inner: &mut stream,
+            recorded: &mut consumed,
+        })?;
+        Ok((
+            result,
+            Spooled {
+                consumed,
+                rest: stream,
+            },
+        ))
+    }
+
+    /// The whole stream: the spooled bytes followed by the rest.
+    pub fn into_reader(self) -> io::Chain<Cursor<Vec<u8>>, R> {
+        Cursor::new(self.consumed).chain(self.rest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::BufRead;
+
+    #[test]
+    fn spooled_bytes_are_replayed() {
+        let stream = Cursor::new(b"id\n1\n2\n3\n".to_vec());
+        let (first, spooled) = Spooled::inspect(stream, |reader| {
+            let mut line = String::new();
+            io::BufReader::with_capacity(4, reader).read_line(&mut line)?;
+            Ok(line)
+        })
+        .unwrap();
+        assert_eq!(first, "id\n");
+        assert_eq!(spooled.consumed.len(), 4);
+
+        let mut content = String::new();
+        spooled.into_reader().read_to_string(&mut content).unwrap();
+        assert_eq!(content, "id\n1\n2\n3\n");
+    }
+
+    #[test]
+    fn read_ahead_beyond_the_sample_is_kept() {
+        let stream = Cursor::new(b"a\nb\nc\n".to_vec());
+        let (_, spooled) = Spooled::inspect(stream, |reader| {
+            let mut buf = [0; 5];
+            reader.read_exact(&mut buf)?;
+            Ok(())
+        })
+        .unwrap();
+
+        let mut content = String::new();
+        spooled.into_reader().read_to_string(&mut content).unwrap();
+        assert_eq!(content, "a\nb\nc\n");
+    }
+}
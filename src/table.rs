@@ -0,0 +1,360 @@
+use anyhow::Result;
+use exarrow_rs::types::{quote_identifier, ExasolType};
+
+use crate::commands::parse_table_name;
+
+/// Resolve an identifier the way Exasol does: quoted identifiers keep their exact
+/// spelling (with `""` unescaped), unquoted identifiers are folded to upper case.
+pub fn normalize_identifier(ident: &str) -> String {
+    match ident
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+    {
+        Some(inner) => inner.replace("\"\"", "\""),
+        None => ident.to_uppercase(),
+    }
+}
+
+/// The name of `table` as Exasol resolves it, schema-qualified if it is given with a
+/// schema: `my_schema.events` becomes `MY_SCHEMA.EVENTS`.
+pub fn qualified_name(table: &str) -> String {
+    let (schema, name) = parse_table_name(table);
+    match schema {
+        Some(schema) => format!(
+            "{}.{}",
+            normalize_identifier(schema),
+            normalize_identifier(name)
+        ),
+        None => normalize_identifier(name),
+    }
+}
+
+/// Render a string as a single-quoted SQL literal.
+pub fn sql_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// Build the `WHERE` predicate matching `table` in the `SYS.EXA_ALL_*` system views.
+/// An unqualified table is looked up in the session's current schema.
+fn catalog_predicate(table: &str, schema_column: &str, name_column: &str) -> String {
+    let (schema, name) = parse_table_name(table);
+    let schema_expr = match schema {
+        Some(s) => sql_literal(&normalize_identifier(s)),
+        None => "CURRENT_SCHEMA".to_string(),
+    };
+    format!(
+        "{schema_column} = {schema_expr} AND {name_column} = {}",
+        sql_literal(&normalize_identifier(name))
+    )
+}
+
+/// Returns whether `table` (optionally schema-qualified) exists.
+pub async fn table_exists(conn: &mut exarrow_rs::Connection, table: &str) -> Result<bool> {
+    let sql = format!(
+        "SELECT TABLE_NAME FROM SYS.EXA_ALL_TABLES WHERE {}",
+        catalog_predicate(table, "TABLE_SCHEMA", "TABLE_NAME")
+    );
+    let batches = conn.query(sql).await?;
+    Ok(batches.iter().any(|b| b.num_rows() > 0))
+}
+
+/// A column of an existing table, as listed in `SYS.EXA_ALL_COLUMNS`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableColumn {
+    pub name: String,
+    /// The type as Exasol reports it, e.g. `VARCHAR(100) UTF8`.
+    pub column_type: String,
+}
+
+impl TableColumn {
+    /// The column type, if exapump understands it.
+    pub fn exasol_type(&self) -> Option<ExasolType> {
+        parse_column_type(&self.column_type)
+    }
+}
+
+/// Returns the columns of `table` in table order, or `None` if the table does not exist.
+pub async fn table_columns(
+    conn: &mut exarrow_rs::Connection,
+    table: &str,
+) -> Result<Option<Vec<TableColumn>>> {
+    if !table_exists(conn, table).await? {
+        return Ok(None);
+    }
+
+    let sql = format!(
+        "SELECT COLUMN_NAME, COLUMN_TYPE FROM SYS.EXA_ALL_COLUMNS WHERE {} \
+         ORDER BY COLUMN_ORDINAL_POSITION",
+        catalog_predicate(table, "COLUMN_SCHEMA", "COLUMN_TABLE")
+    );
+    let mut columns = Vec::new();
+    for batch in conn.query(sql).await? {
+        for row in 0..batch.num_rows() {
+            columns.push(TableColumn {
+                name: arrow::util::display::array_value_to_string(batch.column(0), row)?,
+                column_type: arrow::util::display::array_value_to_string(batch.column(1), row)?,
+            });
+        }
+    }
+    Ok(Some(columns))
+}
+
+/// Parse a `COLUMN_TYPE` value from the Exasol catalog, such as `DECIMAL(18,0)`,
+/// `VARCHAR(100) UTF8` or `TIMESTAMP(3) WITH LOCAL TIME ZONE`.
+pub fn parse_column_type(column_type: &str) -> Option<ExasolType> {
+    let upper = column_type.trim().to_uppercase();
+    let ty = upper
+        .strip_suffix(" UTF8")
+        .or_else(|| upper.strip_suffix(" ASCII"))
+        .unwrap_or(&upper);
+
+    let (base, args) = match ty.split_once('(') {
+        Some((base, rest)) => (base, rest.split_once(')').map(|(args, _)| args)),
+        None => (ty, None),
+    };
+    let arg = |i: usize| -> Option<&str> { args?.split(',').nth(i).map(str::trim) };
+
+    match base.trim() {
+        "BOOLEAN" => Some(ExasolType::Boolean),
+        "DOUBLE" | "DOUBLE PRECISION" => Some(ExasolType::Double),
+        "DATE" => Some(ExasolType::Date),
+        "CHAR" => Some(ExasolType::Char {
+            size: arg(0)?.parse().ok()?,
+        }),
+        "VARCHAR" => Some(ExasolType::Varchar {
+            size: arg(0)?.parse().ok()?,
+        }),
+        "DECIMAL" => Some(ExasolType::Decimal {
+            precision: arg(0)?.parse().ok()?,
+            scale: arg(1).unwrap_or("0").parse().ok()?,
+        }),
+        "TIMESTAMP" => Some(ExasolType::Timestamp {
+            with_local_time_zone: ty.ends_with("WITH LOCAL TIME ZONE"),
+        }),
+        "INTERVAL YEAR" => Some(ExasolType::IntervalYearToMonth),
+        "INTERVAL DAY" => {
+            let precision = ty
+                .rsplit_once("SECOND(")
+                .and_then(|(_, rest)| rest.strip_suffix(')'))
+                .and_then(|p| p.parse().ok())
+                .unwrap_or(3);
+            Some(ExasolType::IntervalDayToSecond { precision })
+        }
+        "GEOMETRY" => Some(ExasolType::Geometry {
+            srid: args.and_then(|a| a.trim().parse().ok()),
+        }),
+        "HASHTYPE" => Some(ExasolType::Hashtype {
+            byte_size: arg(0)?.trim_end_matches("BYTE").trim().parse().ok()?,
+        }),
+        _ => None,
+    }
+}
+
+/// Runs a query returning a single count, such as `SELECT COUNT(*) ...`.
+pub async fn query_count(conn: &mut exarrow_rs::Connection, sql: String) -> Result<u64> {
+    let batches = conn.query(sql).await?;
+    let batch = batches
+        .iter()
+        .find(|b| b.num_rows() > 0)
+        .ok_or_else(|| anyhow::anyhow!("count query returned no rows"))?;
+    let value = arrow::util::display::array_value_to_string(batch.column(0), 0)?;
+    Ok(value.parse()?)
+}
+
+/// Derive the name of a staging table that lives next to `table` in the same schema.
+///
+/// The suffix keeps concurrent uploads into the same target apart. The result is
+/// always quoted so that it refers to exactly the name it was derived from.
+pub fn staging_table_name(table: &str, suffix: &str) -> String {
+    let (schema, name) = parse_table_name(table);
+    let staging = quote_identifier(&format!(
+        "{}_EXAPUMP_STAGING_{suffix}",
+        normalize_identifier(name)
+    ));
+    match schema {
+        Some(s) => format!("{s}.{staging}"),
+        None => staging,
+    }
+}
+
+/// Build the `ON` condition joining target alias `t` and source alias `s` on `keys`.
+fn key_condition(keys: &[String]) -> String {
+    keys.iter()
+        .map(|k| format!("t.{k} = s.{k}"))
+        .collect::<Vec<_>>()
+        .join(" AND ")
+}
+
+/// Build a `MERGE INTO` statement that updates rows of `target` matching `source` on
+/// the `keys` columns and inserts the rest. `columns` and `keys` are identifiers as
+/// they appear in DDL; when every column is a key, matching rows are left untouched.
+pub fn merge_statement(target: &str, source: &str, columns: &[String], keys: &[String]) -> String {
+    let mut sql = format!(
+        "MERGE INTO {target} t USING {source} s ON ({})",
+        key_condition(keys)
+    );
+
+    let updates: Vec<String> = columns
+        .iter()
+        .filter(|c| !keys.contains(c))
+        .map(|c| format!("t.{c} = s.{c}"))
+        .collect();
+    if !updates.is_empty() {
+        sql.push_str(&format!(
+            " WHEN MATCHED THEN UPDATE SET {}",
+            updates.join(", ")
+        ));
+    }
+
+    let values: Vec<String> = columns.iter().map(|c| format!("s.{c}")).collect();
+    sql.push_str(&format!(
+        " WHEN NOT MATCHED THEN INSERT ({}) VALUES ({})",
+        columns.join(", "),
+        values.join(", ")
+    ));
+    sql
+}
+
+/// Build a query counting the rows of `source` that have a match in `target` on `keys`.
+pub fn matched_rows_query(target: &str, source: &str, keys: &[String]) -> String {
+    format!(
+        "SELECT COUNT(*) FROM {source} s WHERE EXISTS (SELECT 1 FROM {target} t WHERE {})",
+        key_condition(keys)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unquoted_identifier_is_uppercased() {
+        assert_eq!(normalize_identifier("my_table"), "MY_TABLE");
+    }
+
+    #[test]
+    fn quoted_identifier_keeps_case() {
+        assert_eq!(normalize_identifier("\"MyTable\""), "MyTable");
+        assert_eq!(normalize_identifier("\"a\"\"b\""), "a\"b");
+    }
+
+    #[test]
+    fn literal_escapes_quotes() {
+        assert_eq!(sql_literal("O'Brien"), "'O''Brien'");
+    }
+
+    #[test]
+    fn predicate_uses_current_schema_for_unqualified_table() {
+        assert_eq!(
+            catalog_predicate("events", "TABLE_SCHEMA", "TABLE_NAME"),
+            "TABLE_SCHEMA = CURRENT_SCHEMA AND TABLE_NAME = 'EVENTS'"
+        );
+    }
+
+    #[test]
+    fn predicate_normalizes_schema_and_table() {
+        assert_eq!(
+            catalog_predicate("raw.\"Events\"", "TABLE_SCHEMA", "TABLE_NAME"),
+            "TABLE_SCHEMA = 'RAW' AND TABLE_NAME = 'Events'"
+        );
+    }
+
+    #[test]
+    fn staging_name_keeps_schema() {
+        assert_eq!(
+            staging_table_name("raw.events", "42"),
+            "raw.\"EVENTS_EXAPUMP_STAGING_42\""
+        );
+    }
+
+    #[test]
+    fn staging_name_of_quoted_table_keeps_case() {
+        assert_eq!(
+            staging_table_name("\"Events\"", "42"),
+            "\"Events_EXAPUMP_STAGING_42\""
+        );
+    }
+
+    fn idents(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| format!("\"{n}\"")).collect()
+    }
+
+    #[test]
+    fn merge_updates_non_key_columns_and_inserts_all() {
+        let sql = merge_statement(
+            "raw.events",
+            "raw.staging",
+            &idents(&["id", "valid_from", "value"]),
+            &idents(&["id", "valid_from"]),
+        );
+        assert_eq!(
+            sql,
+            "MERGE INTO raw.events t USING raw.staging s \
+             ON (t.\"id\" = s.\"id\" AND t.\"valid_from\" = s.\"valid_from\") \
+             WHEN MATCHED THEN UPDATE SET t.\"value\" = s.\"value\" \
+             WHEN NOT MATCHED THEN INSERT (\"id\", \"valid_from\", \"value\") \
+             VALUES (s.\"id\", s.\"valid_from\", s.\"value\")"
+        );
+    }
+
+    #[test]
+    fn merge_on_all_columns_only_inserts() {
+        let sql = merge_statement("events", "staging", &idents(&["id"]), &idents(&["id"]));
+        assert!(!sql.contains("WHEN MATCHED"), "got: {sql}");
+        assert!(sql.contains("WHEN NOT MATCHED THEN INSERT"), "got: {sql}");
+    }
+
+    #[test]
+    fn matched_rows_query_joins_on_keys() {
+        assert_eq!(
+            matched_rows_query("events", "staging", &idents(&["id"])),
+            "SELECT COUNT(*) FROM staging s WHERE EXISTS \
+             (SELECT 1 FROM events t WHERE t.\"id\" = s.\"id\")"
+        );
+    }
+
+    #[test]
+    fn parses_catalog_column_types() {
+        let cases = [
+            (
+                "DECIMAL(18,0)",
+                ExasolType::Decimal {
+                    precision: 18,
+                    scale: 0,
+                },
+            ),
+            ("VARCHAR(100) UTF8", ExasolType::Varchar { size: 100 }),
+            ("CHAR(1) ASCII", ExasolType::Char { size: 1 }),
+            ("DOUBLE", ExasolType::Double),
+            ("BOOLEAN", ExasolType::Boolean),
+            ("DATE", ExasolType::Date),
+            (
+                "TIMESTAMP(3)",
+                ExasolType::Timestamp {
+                    with_local_time_zone: false,
+                },
+            ),
+            (
+                "TIMESTAMP(3) WITH LOCAL TIME ZONE",
+                ExasolType::Timestamp {
+                    with_local_time_zone: true,
+                },
+            ),
+            ("INTERVAL YEAR(2) TO MONTH", ExasolType::IntervalYearToMonth),
+            (
+                "INTERVAL DAY(2) TO SECOND(3)",
+                ExasolType::IntervalDayToSecond { precision: 3 },
+            ),
+            ("GEOMETRY(4326)", ExasolType::Geometry { srid: Some(4326) }),
+            ("HASHTYPE(16 BYTE)", ExasolType::Hashtype { byte_size: 16 }),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(parse_column_type(input), Some(expected), "input: {input}");
+        }
+    }
+
+    #[test]
+    fn unknown_column_type_is_none() {
+        assert_eq!(parse_column_type("SOMETHING(1)"), None);
+    }
+}
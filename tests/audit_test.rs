@@ -0,0 +1,113 @@
+mod fixtures;
+
+use predicates::prelude::*;
+
+#[test]
+fn audit_table_refuses_stdin() {
+    fixtures::exapump()
+        .args([
+            "upload",
+            "-",
+            "--format",
+            "csv",
+            "--table",
+            "my_schema.events",
+            "--audit-table",
+            "ops.load_log",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+        ])
+        .write_stdin("id\n1\n")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "--audit-table cannot be used with stdin",
+        ));
+}
+
+#[test]
+fn on_duplicate_file_requires_audit_table() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = fixtures::create_test_csv(dir.path());
+
+    fixtures::exapump()
+        .args([
+            "upload",
+            path.to_str().unwrap(),
+            "--table",
+            "my_schema.events",
+            "--on-duplicate-file",
+            "skip",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--audit-table"));
+}
+
+#[tokio::test]
+async fn exasol_audit_table_records_files_and_refuses_duplicates() {
+    fixtures::require_exasol!();
+
+    let (mut conn, schema_name) = fixtures::setup_exasol_schema("EXAPUMP_AUDIT").await;
+    let dir = tempfile::tempdir().unwrap();
+    let first = fixtures::create_csv_with_content(dir.path(), "a.csv", "id\n1\n2\n");
+    let copy = fixtures::create_csv_with_content(dir.path(), "copy.csv", "id\n1\n2\n");
+    let second = fixtures::create_csv_with_content(dir.path(), "b.csv", "id\n3\n");
+    let table_name = format!("{schema_name}.EVENTS");
+    let audit_table = format!("{schema_name}.LOAD_LOG");
+
+    let upload = |files: &[&std::path::Path], extra: &[&str]| {
+        let mut cmd = fixtures::exapump();
+        cmd.timeout(std::time::Duration::from_secs(60))
+            .env("EXAPUMP_CONFIG", dir.path().join("config.toml"))
+            .arg("upload")
+            .args(files.iter().map(|f| f.to_str().unwrap()))
+            .args([
+                "--table",
+                &table_name,
+                "--audit-table",
+                &audit_table,
+                "--dsn",
+                fixtures::DOCKER_DSN,
+            ])
+            .args(extra);
+        cmd
+    };
+
+    upload(&[&first], &[]).assert().success();
+
+    upload(&[&copy, &second], &[])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("already loaded"))
+        .stderr(predicate::str::contains("a.csv"));
+
+    upload(&[&copy, &second], &["--on-duplicate-file", "skip"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Warning: skipping"));
+
+    let rs = conn
+        .execute(&format!("SELECT * FROM {table_name}"))
+        .await
+        .unwrap();
+    let batches = rs.fetch_all().await.unwrap();
+    let row_count: usize = batches.iter().map(|b| b.num_rows()).sum();
+    assert_eq!(row_count, 3, "expected 3 rows in EVENTS");
+
+    let rs = conn
+        .execute(&format!(
+            "SELECT FILE_NAME, ROWS_LOADED, TARGET_TABLE FROM {audit_table} ORDER BY FILE_NAME"
+        ))
+        .await
+        .unwrap();
+    let batches = rs.fetch_all().await.unwrap();
+    let logged: usize = batches.iter().map(|b| b.num_rows()).sum();
+    assert_eq!(logged, 2, "expected a.csv and b.csv in LOAD_LOG");
+
+    let _ = conn
+        .execute_update(&format!("DROP SCHEMA {schema_name} CASCADE"))
+        .await;
+}
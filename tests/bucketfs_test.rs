@@ -259,3 +259,98 @@ fn download_file_not_found() {
             predicate::str::contains("not found").or(predicate::str::contains("File not found")),
         );
 }
+
+#[test]
+fn upload_via_bucketfs_rejects_json_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("events.jsonl");
+    std::fs::write(&path, "{\"id\": 1}\n").unwrap();
+
+    fixtures::exapump()
+        .args([
+            "upload",
+            path.to_str().unwrap(),
+            "--table",
+            "my_schema.events",
+            "--via-bucketfs",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+            "--dry-run",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "--via-bucketfs only applies to CSV and Parquet files",
+        ));
+}
+
+#[test]
+fn upload_via_bucketfs_rejects_zstd_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("data.csv.zst");
+    let encoded = zstd::encode_all(&b"id\n1\n"[..], 0).unwrap();
+    std::fs::write(&path, encoded).unwrap();
+
+    fixtures::exapump()
+        .args([
+            "upload",
+            path.to_str().unwrap(),
+            "--table",
+            "my_schema.events",
+            "--via-bucketfs",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+            "--dry-run",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Exasol only reads gzip and bzip2 compressed files",
+        ));
+}
+
+#[tokio::test]
+async fn exasol_upload_via_bucketfs() {
+    // BucketFS runs in the same container as the database.
+    fixtures::require_exasol!();
+    let write_pw = fixtures::bfs_write_password();
+    let dir = tempfile::tempdir().unwrap();
+    let config_path = write_bfs_config(dir.path(), &write_pw);
+
+    let (mut conn, schema_name) = fixtures::setup_exasol_schema("EXAPUMP_BFS").await;
+    let csv_path = fixtures::create_test_csv(dir.path());
+    let table_name = format!("{schema_name}.STAGED");
+
+    bfs_cmd(&config_path)
+        .timeout(std::time::Duration::from_secs(60))
+        .args([
+            "upload",
+            csv_path.to_str().unwrap(),
+            "--table",
+            &table_name,
+            "--via-bucketfs",
+            "--profile",
+            "bfs",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Imported 3 rows"));
+
+    let rs = conn
+        .execute(&format!("SELECT * FROM {table_name}"))
+        .await
+        .unwrap();
+    let batches = rs.fetch_all().await.unwrap();
+    let row_count: usize = batches.iter().map(|b| b.num_rows()).sum();
+    assert_eq!(row_count, 3, "expected 3 rows in STAGED");
+
+    bfs_cmd(&config_path)
+        .args(["bucketfs", "ls", "--recursive", "--profile", "bfs"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("test.csv").not());
+
+    let _ = conn
+        .execute_update(&format!("DROP SCHEMA {schema_name} CASCADE"))
+        .await;
+}
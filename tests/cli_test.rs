@@ -58,7 +58,31 @@ fn upload_help_shows_all_arguments() {
         .stdout(predicate::str::contains("--no-header"))
         .stdout(predicate::str::contains("--quote"))
         .stdout(predicate::str::contains("--escape"))
-        .stdout(predicate::str::contains("--null-value"));
+        .stdout(predicate::str::contains("--null-value"))
+        .stdout(predicate::str::contains("--recursive"))
+        .stdout(predicate::str::contains("--parallel"))
+        .stdout(predicate::str::contains("--atomic"))
+        .stdout(predicate::str::contains("--mode"))
+        .stdout(predicate::str::contains("--merge-on"))
+        .stdout(predicate::str::contains("--on-schema-mismatch"))
+        .stdout(predicate::str::contains("--evolve-schema"))
+        .stdout(predicate::str::contains("--widen-columns"))
+        .stdout(predicate::str::contains("--columns"))
+        .stdout(predicate::str::contains("--map"))
+        .stdout(predicate::str::contains("--type"))
+        .stdout(predicate::str::contains("--schema-file"))
+        .stdout(predicate::str::contains("--infer-rows"))
+        .stdout(predicate::str::contains("--column-names"))
+        .stdout(predicate::str::contains("--nested"))
+        .stdout(predicate::str::contains("--format"))
+        .stdout(predicate::str::contains("--max-errors"))
+        .stdout(predicate::str::contains("--reject-file"))
+        .stdout(predicate::str::contains("--via-bucketfs"))
+        .stdout(predicate::str::contains("--resume"))
+        .stdout(predicate::str::contains("--audit-table"))
+        .stdout(predicate::str::contains("--on-duplicate-file"))
+        .stdout(predicate::str::contains("--add-column"))
+        .stdout(predicate::str::contains("--partition-columns"));
 }
 
 #[test]
@@ -77,6 +101,82 @@ fn csv_flags_shown_with_defaults_in_help() {
         );
 }
 
+#[test]
+fn upload_parallel_zero_is_rejected() {
+    fixtures::exapump()
+        .args([
+            "upload",
+            "data.csv",
+            "--table",
+            "my_table",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+            "--parallel",
+            "0",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--parallel"));
+}
+
+#[test]
+fn upload_unknown_mode_is_rejected() {
+    fixtures::exapump()
+        .args([
+            "upload",
+            "data.csv",
+            "--table",
+            "my_table",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+            "--mode",
+            "overwrite",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("append"))
+        .stderr(predicate::str::contains("truncate"))
+        .stderr(predicate::str::contains("replace"))
+        .stderr(predicate::str::contains("create"));
+}
+
+#[test]
+fn upload_merge_on_conflicts_with_mode() {
+    fixtures::exapump()
+        .args([
+            "upload",
+            "data.csv",
+            "--table",
+            "my_table",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+            "--merge-on",
+            "id",
+            "--mode",
+            "truncate",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn upload_widen_columns_requires_evolve_schema() {
+    fixtures::exapump()
+        .args([
+            "upload",
+            "data.csv",
+            "--table",
+            "my_table",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+            "--widen-columns",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--evolve-schema"));
+}
+
 #[test]
 fn missing_required_arguments() {
     fixtures::exapump()
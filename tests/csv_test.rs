@@ -469,3 +469,1174 @@ async fn exasol_csv_flags_ignored_for_parquet() {
         .execute_update(&format!("DROP SCHEMA {schema_name} CASCADE"))
         .await;
 }
+
+#[test]
+fn csv_dry_run_multiple_files_merges_schema() {
+    let dir = tempfile::tempdir().unwrap();
+    let first = fixtures::create_csv_with_content(dir.path(), "a.csv", "id,score\n1,10\n2,20\n");
+    let second =
+        fixtures::create_csv_with_content(dir.path(), "b.csv", "id,score\n3,30.5\n4,40.25\n");
+
+    fixtures::exapump()
+        .args([
+            "upload",
+            first.to_str().unwrap(),
+            second.to_str().unwrap(),
+            "--table",
+            "my_table",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+            "--dry-run",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"score\": DOUBLE"))
+        .stdout(predicate::str::contains("CREATE TABLE").count(1));
+}
+
+#[test]
+fn csv_multiple_files_with_mismatched_columns_fail() {
+    let dir = tempfile::tempdir().unwrap();
+    let first = fixtures::create_csv_with_content(dir.path(), "a.csv", "id,name\n1,alice\n");
+    let second = fixtures::create_csv_with_content(dir.path(), "b.csv", "id,email\n2,b@x.io\n");
+
+    fixtures::exapump()
+        .args([
+            "upload",
+            first.to_str().unwrap(),
+            second.to_str().unwrap(),
+            "--table",
+            "my_table",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("schema mismatch"))
+        .stderr(predicate::str::contains("b.csv"));
+}
+
+#[test]
+fn csv_multiple_files_one_missing_fails() {
+    let dir = tempfile::tempdir().unwrap();
+    let csv_path = fixtures::create_test_csv(dir.path());
+
+    fixtures::exapump()
+        .args([
+            "upload",
+            csv_path.to_str().unwrap(),
+            "missing.csv",
+            "--table",
+            "my_table",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+            "--dry-run",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("missing.csv"));
+}
+
+#[test]
+fn csv_and_parquet_files_cannot_be_mixed() {
+    let dir = tempfile::tempdir().unwrap();
+    let csv_path = fixtures::create_test_csv(dir.path());
+    let parquet_path = fixtures::create_test_parquet(dir.path());
+
+    fixtures::exapump()
+        .args([
+            "upload",
+            csv_path.to_str().unwrap(),
+            parquet_path.to_str().unwrap(),
+            "--table",
+            "my_table",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+            "--dry-run",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot mix file formats"));
+}
+
+#[tokio::test]
+async fn exasol_csv_import_multiple_files() {
+    fixtures::require_exasol!();
+
+    let (mut conn, schema_name) = fixtures::setup_exasol_schema("EXAPUMP_CSV").await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let first = fixtures::create_csv_with_content(dir.path(), "a.csv", "id,name\n1,alice\n2,bob\n");
+    let second =
+        fixtures::create_csv_with_content(dir.path(), "b.csv", "id,name\n3,carol\n4,dave\n5,eve\n");
+
+    let table_name = format!("{schema_name}.MULTI_FILE");
+
+    fixtures::exapump()
+        .timeout(std::time::Duration::from_secs(60))
+        .args([
+            "upload",
+            first.to_str().unwrap(),
+            second.to_str().unwrap(),
+            "--table",
+            &table_name,
+            "--dsn",
+            fixtures::DOCKER_DSN,
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("a.csv: 2 rows"))
+        .stdout(predicate::str::contains("b.csv: 3 rows"))
+        .stdout(predicate::str::contains("Imported 5 rows from 2 files"));
+
+    let rs = conn
+        .execute(&format!("SELECT * FROM {table_name}"))
+        .await
+        .unwrap();
+    let batches = rs.fetch_all().await.unwrap();
+    let row_count: usize = batches.iter().map(|b| b.num_rows()).sum();
+    assert_eq!(row_count, 5, "expected 5 rows in MULTI_FILE");
+
+    let _ = conn
+        .execute_update(&format!("DROP SCHEMA {schema_name} CASCADE"))
+        .await;
+}
+
+#[tokio::test]
+async fn exasol_csv_atomic_import_creates_table_and_drops_staging() {
+    fixtures::require_exasol!();
+
+    let (mut conn, schema_name) = fixtures::setup_exasol_schema("EXAPUMP_CSV").await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let first = fixtures::create_csv_with_content(dir.path(), "a.csv", "id,name\n1,alice\n2,bob\n");
+    let second = fixtures::create_csv_with_content(dir.path(), "b.csv", "id,name\n3,carol\n");
+
+    let table_name = format!("{schema_name}.ATOMIC_NEW");
+
+    fixtures::exapump()
+        .timeout(std::time::Duration::from_secs(60))
+        .args([
+            "upload",
+            first.to_str().unwrap(),
+            second.to_str().unwrap(),
+            "--table",
+            &table_name,
+            "--atomic",
+            "--dsn",
+            fixtures::DOCKER_DSN,
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Imported 3 rows from 2 files"));
+
+    let rs = conn
+        .execute(&format!("SELECT * FROM {table_name}"))
+        .await
+        .unwrap();
+    let batches = rs.fetch_all().await.unwrap();
+    let row_count: usize = batches.iter().map(|b| b.num_rows()).sum();
+    assert_eq!(row_count, 3, "expected 3 rows in ATOMIC_NEW");
+
+    let rs = conn
+        .execute(&format!(
+            "SELECT TABLE_NAME FROM SYS.EXA_ALL_TABLES WHERE TABLE_SCHEMA = '{schema_name}' AND TABLE_NAME LIKE '%EXAPUMP_STAGING%'"
+        ))
+        .await
+        .unwrap();
+    let batches = rs.fetch_all().await.unwrap();
+    let staging_count: usize = batches.iter().map(|b| b.num_rows()).sum();
+    assert_eq!(staging_count, 0, "staging table should have been dropped");
+
+    let _ = conn
+        .execute_update(&format!("DROP SCHEMA {schema_name} CASCADE"))
+        .await;
+}
+
+#[tokio::test]
+async fn exasol_csv_atomic_import_leaves_target_untouched_on_failure() {
+    fixtures::require_exasol!();
+
+    let (mut conn, schema_name) = fixtures::setup_exasol_schema("EXAPUMP_CSV").await;
+
+    let table_name = format!("{schema_name}.ATOMIC_EXISTING");
+    conn.execute_update(&format!(
+        "CREATE TABLE {table_name} (\"id\" DECIMAL(2,0), \"name\" VARCHAR(20))"
+    ))
+    .await
+    .unwrap();
+    conn.execute_update(&format!("INSERT INTO {table_name} VALUES (1, 'existing')"))
+        .await
+        .unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let first = fixtures::create_csv_with_content(dir.path(), "a.csv", "id,name\n2,alice\n");
+    // 1000 does not fit DECIMAL(2,0), so moving the staged rows into the target fails
+    let second = fixtures::create_csv_with_content(dir.path(), "b.csv", "id,name\n1000,bob\n");
+
+    fixtures::exapump()
+        .timeout(std::time::Duration::from_secs(60))
+        .args([
+            "upload",
+            first.to_str().unwrap(),
+            second.to_str().unwrap(),
+            "--table",
+            &table_name,
+            "--atomic",
+            "--dsn",
+            fixtures::DOCKER_DSN,
+        ])
+        .assert()
+        .failure();
+
+    let rs = conn
+        .execute(&format!("SELECT * FROM {table_name}"))
+        .await
+        .unwrap();
+    let batches = rs.fetch_all().await.unwrap();
+    let row_count: usize = batches.iter().map(|b| b.num_rows()).sum();
+    assert_eq!(
+        row_count, 1,
+        "target table should keep only its original row"
+    );
+
+    let _ = conn
+        .execute_update(&format!("DROP SCHEMA {schema_name} CASCADE"))
+        .await;
+}
+
+#[tokio::test]
+async fn exasol_csv_mode_replace_and_truncate() {
+    fixtures::require_exasol!();
+
+    let (mut conn, schema_name) = fixtures::setup_exasol_schema("EXAPUMP_CSV").await;
+
+    let table_name = format!("{schema_name}.SNAPSHOT");
+    conn.execute_update(&format!(
+        "CREATE TABLE {table_name} (\"id\" DECIMAL(18,0), \"name\" VARCHAR(20))"
+    ))
+    .await
+    .unwrap();
+    conn.execute_update(&format!(
+        "INSERT INTO {table_name} VALUES (1, 'old'), (2, 'old')"
+    ))
+    .await
+    .unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let csv_path =
+        fixtures::create_csv_with_content(dir.path(), "snapshot.csv", "id,name,extra\n3,new,x\n");
+
+    fixtures::exapump()
+        .timeout(std::time::Duration::from_secs(60))
+        .args([
+            "upload",
+            csv_path.to_str().unwrap(),
+            "--table",
+            &table_name,
+            "--mode",
+            "replace",
+            "--dsn",
+            fixtures::DOCKER_DSN,
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Imported 1 rows"));
+
+    let rs = conn
+        .execute(&format!("SELECT \"extra\" FROM {table_name}"))
+        .await
+        .unwrap();
+    let batches = rs.fetch_all().await.unwrap();
+    let row_count: usize = batches.iter().map(|b| b.num_rows()).sum();
+    assert_eq!(
+        row_count, 1,
+        "replace should recreate the table with the new columns"
+    );
+
+    fixtures::exapump()
+        .timeout(std::time::Duration::from_secs(60))
+        .args([
+            "upload",
+            csv_path.to_str().unwrap(),
+            "--table",
+            &table_name,
+            "--mode",
+            "truncate",
+            "--dsn",
+            fixtures::DOCKER_DSN,
+        ])
+        .assert()
+        .success();
+
+    let rs = conn
+        .execute(&format!("SELECT * FROM {table_name}"))
+        .await
+        .unwrap();
+    let batches = rs.fetch_all().await.unwrap();
+    let row_count: usize = batches.iter().map(|b| b.num_rows()).sum();
+    assert_eq!(
+        row_count, 1,
+        "truncate should remove the previously loaded rows"
+    );
+
+    let _ = conn
+        .execute_update(&format!("DROP SCHEMA {schema_name} CASCADE"))
+        .await;
+}
+
+#[tokio::test]
+async fn exasol_csv_mode_create_fails_if_table_exists() {
+    fixtures::require_exasol!();
+
+    let (mut conn, schema_name) = fixtures::setup_exasol_schema("EXAPUMP_CSV").await;
+
+    let table_name = format!("{schema_name}.EXISTING");
+    conn.execute_update(&format!("CREATE TABLE {table_name} (\"id\" DECIMAL(18,0))"))
+        .await
+        .unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let csv_path = fixtures::create_csv_with_content(dir.path(), "data.csv", "id\n1\n");
+
+    fixtures::exapump()
+        .timeout(std::time::Duration::from_secs(60))
+        .args([
+            "upload",
+            csv_path.to_str().unwrap(),
+            "--table",
+            &table_name,
+            "--mode",
+            "create",
+            "--dsn",
+            fixtures::DOCKER_DSN,
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("already exists"));
+
+    let rs = conn
+        .execute(&format!("SELECT * FROM {table_name}"))
+        .await
+        .unwrap();
+    let batches = rs.fetch_all().await.unwrap();
+    let row_count: usize = batches.iter().map(|b| b.num_rows()).sum();
+    assert_eq!(
+        row_count, 0,
+        "no rows should be loaded into an existing table"
+    );
+
+    let _ = conn
+        .execute_update(&format!("DROP SCHEMA {schema_name} CASCADE"))
+        .await;
+}
+
+#[test]
+fn csv_merge_on_unknown_column_fails() {
+    let dir = tempfile::tempdir().unwrap();
+    let csv_path = fixtures::create_csv_with_content(dir.path(), "data.csv", "id,name\n1,alice\n");
+
+    fixtures::exapump()
+        .args([
+            "upload",
+            csv_path.to_str().unwrap(),
+            "--table",
+            "my_table",
+            "--merge-on",
+            "id,missing",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+            "--dry-run",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("merge column missing not found"));
+}
+
+#[tokio::test]
+async fn exasol_csv_merge_on_updates_and_inserts() {
+    fixtures::require_exasol!();
+
+    let (mut conn, schema_name) = fixtures::setup_exasol_schema("EXAPUMP_CSV").await;
+
+    let table_name = format!("{schema_name}.MERGED");
+    conn.execute_update(&format!(
+        "CREATE TABLE {table_name} (\"id\" DECIMAL(18,0), \"name\" VARCHAR(2000000))"
+    ))
+    .await
+    .unwrap();
+    conn.execute_update(&format!(
+        "INSERT INTO {table_name} VALUES (1, 'alice'), (2, 'bob')"
+    ))
+    .await
+    .unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let csv_path = fixtures::create_csv_with_content(
+        dir.path(),
+        "corrections.csv",
+        "id,name\n2,robert\n3,carol\n",
+    );
+
+    fixtures::exapump()
+        .timeout(std::time::Duration::from_secs(60))
+        .args([
+            "upload",
+            csv_path.to_str().unwrap(),
+            "--table",
+            &table_name,
+            "--merge-on",
+            "id",
+            "--dsn",
+            fixtures::DOCKER_DSN,
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 rows inserted, 1 rows updated"));
+
+    let rs = conn
+        .execute(&format!(
+            "SELECT \"name\" FROM {table_name} WHERE \"id\" = 2"
+        ))
+        .await
+        .unwrap();
+    let batches = rs.fetch_all().await.unwrap();
+    let name = arrow::util::display::array_value_to_string(batches[0].column(0), 0).unwrap();
+    assert_eq!(name, "robert");
+
+    let rs = conn
+        .execute(&format!("SELECT * FROM {table_name}"))
+        .await
+        .unwrap();
+    let batches = rs.fetch_all().await.unwrap();
+    let row_count: usize = batches.iter().map(|b| b.num_rows()).sum();
+    assert_eq!(row_count, 3, "expected 3 rows after merge");
+
+    let _ = conn
+        .execute_update(&format!("DROP SCHEMA {schema_name} CASCADE"))
+        .await;
+}
+
+#[tokio::test]
+async fn exasol_csv_schema_mismatch_aborts_before_loading() {
+    fixtures::require_exasol!();
+
+    let (mut conn, schema_name) = fixtures::setup_exasol_schema("EXAPUMP_CSV").await;
+
+    let table_name = format!("{schema_name}.MISMATCH");
+    conn.execute_update(&format!(
+        "CREATE TABLE {table_name} (ID DECIMAL(18,0), NAME VARCHAR(100))"
+    ))
+    .await
+    .unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let csv_path =
+        fixtures::create_csv_with_content(dir.path(), "data.csv", "id,email\n1,a@example.com\n");
+
+    fixtures::exapump()
+        .timeout(std::time::Duration::from_secs(60))
+        .args([
+            "upload",
+            csv_path.to_str().unwrap(),
+            "--table",
+            &table_name,
+            "--dsn",
+            fixtures::DOCKER_DSN,
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("+ email"))
+        .stderr(predicate::str::contains("- NAME"))
+        .stderr(predicate::str::contains("does not match the file columns"));
+
+    let rs = conn
+        .execute(&format!("SELECT * FROM {table_name}"))
+        .await
+        .unwrap();
+    let batches = rs.fetch_all().await.unwrap();
+    let row_count: usize = batches.iter().map(|b| b.num_rows()).sum();
+    assert_eq!(
+        row_count, 0,
+        "no rows should be loaded after a schema mismatch"
+    );
+
+    let _ = conn
+        .execute_update(&format!("DROP SCHEMA {schema_name} CASCADE"))
+        .await;
+}
+
+#[tokio::test]
+async fn exasol_csv_evolve_schema_adds_new_columns() {
+    fixtures::require_exasol!();
+
+    let (mut conn, schema_name) = fixtures::setup_exasol_schema("EXAPUMP_CSV").await;
+
+    let table_name = format!("{schema_name}.EVOLVING");
+    conn.execute_update(&format!(
+        "CREATE TABLE {table_name} (ID DECIMAL(18,0), NAME VARCHAR(10))"
+    ))
+    .await
+    .unwrap();
+    conn.execute_update(&format!("INSERT INTO {table_name} VALUES (1, 'alice')"))
+        .await
+        .unwrap();
+
+    // the new column sits between the existing ones in the file
+    let dir = tempfile::tempdir().unwrap();
+    let csv_path = fixtures::create_csv_with_content(
+        dir.path(),
+        "data.csv",
+        "id,email,name\n2,bob@example.com,bob\n",
+    );
+
+    fixtures::exapump()
+        .timeout(std::time::Duration::from_secs(60))
+        .args([
+            "upload",
+            csv_path.to_str().unwrap(),
+            "--table",
+            &table_name,
+            "--evolve-schema",
+            "--widen-columns",
+            "--type",
+            "name=VARCHAR(50)",
+            "--dsn",
+            fixtures::DOCKER_DSN,
+        ])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Added column email"))
+        .stderr(predicate::str::contains("Widened column name of"))
+        .stderr(predicate::str::contains("to VARCHAR(50)"));
+
+    let rs = conn
+        .execute(&format!(
+            "SELECT NAME FROM {table_name} WHERE \"email\" = 'bob@example.com'"
+        ))
+        .await
+        .unwrap();
+    let batches = rs.fetch_all().await.unwrap();
+    let name = arrow::util::display::array_value_to_string(batches[0].column(0), 0).unwrap();
+    assert_eq!(name, "bob");
+
+    let _ = conn
+        .execute_update(&format!("DROP SCHEMA {schema_name} CASCADE"))
+        .await;
+}
+
+#[tokio::test]
+async fn exasol_csv_widen_columns_keeps_size_of_unsized_strings() {
+    fixtures::require_exasol!();
+
+    let (mut conn, schema_name) = fixtures::setup_exasol_schema("EXAPUMP_CSV").await;
+
+    let table_name = format!("{schema_name}.SHORT_NAMES");
+    conn.execute_update(&format!(
+        "CREATE TABLE {table_name} (ID DECIMAL(18,0), NAME VARCHAR(20))"
+    ))
+    .await
+    .unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let csv_path =
+        fixtures::create_csv_with_content(dir.path(), "data.csv", "id,name\n1,alice\n2,bob\n");
+
+    fixtures::exapump()
+        .timeout(std::time::Duration::from_secs(60))
+        .args([
+            "upload",
+            csv_path.to_str().unwrap(),
+            "--table",
+            &table_name,
+            "--evolve-schema",
+            "--widen-columns",
+            "--dsn",
+            fixtures::DOCKER_DSN,
+        ])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Widened").not());
+
+    let rs = conn
+        .execute(&format!(
+            "SELECT COLUMN_TYPE FROM SYS.EXA_ALL_COLUMNS \
+             WHERE COLUMN_SCHEMA = '{schema_name}' AND COLUMN_TABLE = 'SHORT_NAMES' \
+             AND COLUMN_NAME = 'NAME'"
+        ))
+        .await
+        .unwrap();
+    let batches = rs.fetch_all().await.unwrap();
+    let column_type = arrow::util::display::array_value_to_string(batches[0].column(0), 0).unwrap();
+    assert!(column_type.starts_with("VARCHAR(20)"), "got: {column_type}");
+
+    let _ = conn
+        .execute_update(&format!("DROP SCHEMA {schema_name} CASCADE"))
+        .await;
+}
+
+#[test]
+fn csv_dry_run_shows_column_mapping() {
+    let dir = tempfile::tempdir().unwrap();
+    let csv_path =
+        fixtures::create_csv_with_content(dir.path(), "data.csv", "id,name,notes\n1,alice,x\n");
+
+    fixtures::exapump()
+        .args([
+            "upload",
+            csv_path.to_str().unwrap(),
+            "--table",
+            "my_table",
+            "--columns",
+            "name,id",
+            "--map",
+            "id=customer_id",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+            "--dry-run",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Column mapping:"))
+        .stdout(predicate::str::contains("name -> \"name\""))
+        .stdout(predicate::str::contains("id -> \"customer_id\""))
+        .stdout(predicate::str::contains("notes").not());
+}
+
+#[test]
+fn csv_unknown_column_in_columns_fails() {
+    let dir = tempfile::tempdir().unwrap();
+    let csv_path = fixtures::create_csv_with_content(dir.path(), "data.csv", "id,name\n1,alice\n");
+
+    fixtures::exapump()
+        .args([
+            "upload",
+            csv_path.to_str().unwrap(),
+            "--table",
+            "my_table",
+            "--columns",
+            "id,email",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+            "--dry-run",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("column email not found"));
+}
+
+#[tokio::test]
+async fn exasol_csv_column_subset_into_table_with_defaulted_column() {
+    fixtures::require_exasol!();
+
+    let (mut conn, schema_name) = fixtures::setup_exasol_schema("EXAPUMP_CSV").await;
+
+    let table_name = format!("{schema_name}.MAPPED");
+    conn.execute_update(&format!(
+        "CREATE TABLE {table_name} (\
+            CUSTOMER_ID DECIMAL(18,0), \
+            NAME VARCHAR(100), \
+            LOADED_AT TIMESTAMP DEFAULT CURRENT_TIMESTAMP\
+        )"
+    ))
+    .await
+    .unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let csv_path = fixtures::create_csv_with_content(
+        dir.path(),
+        "data.csv",
+        "notes,name,id\nignored,alice,1\nignored,bob,2\n",
+    );
+
+    fixtures::exapump()
+        .timeout(std::time::Duration::from_secs(60))
+        .args([
+            "upload",
+            csv_path.to_str().unwrap(),
+            "--table",
+            &table_name,
+            "--columns",
+            "id,name",
+            "--map",
+            "id=CUSTOMER_ID",
+            "--dsn",
+            fixtures::DOCKER_DSN,
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Imported 2 rows"));
+
+    let rs = conn
+        .execute(&format!(
+            "SELECT NAME FROM {table_name} WHERE CUSTOMER_ID = 2 AND LOADED_AT IS NOT NULL"
+        ))
+        .await
+        .unwrap();
+    let batches = rs.fetch_all().await.unwrap();
+    let name = arrow::util::display::array_value_to_string(batches[0].column(0), 0).unwrap();
+    assert_eq!(name, "bob");
+
+    let _ = conn
+        .execute_update(&format!("DROP SCHEMA {schema_name} CASCADE"))
+        .await;
+}
+
+#[test]
+fn csv_dry_run_applies_type_overrides() {
+    let dir = tempfile::tempdir().unwrap();
+    let csv_path = fixtures::create_csv_with_content(
+        dir.path(),
+        "data.csv",
+        "id,zip,amount\n1,01234,1.50\n2,99999,2.25\n",
+    );
+    let schema_file = dir.path().join("types.sql");
+    std::fs::write(&schema_file, "-- money\namount DECIMAL(12,2)\n").unwrap();
+
+    fixtures::exapump()
+        .args([
+            "upload",
+            csv_path.to_str().unwrap(),
+            "--table",
+            "my_table",
+            "--type",
+            "zip=VARCHAR(10)",
+            "--schema-file",
+            schema_file.to_str().unwrap(),
+            "--dsn",
+            fixtures::DUMMY_DSN,
+            "--dry-run",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"zip\": VARCHAR(10)"))
+        .stdout(predicate::str::contains("\"zip\" VARCHAR(10)"))
+        .stdout(predicate::str::contains("\"amount\" DECIMAL(12,2)"));
+}
+
+#[test]
+fn csv_type_override_for_unknown_column_fails() {
+    let dir = tempfile::tempdir().unwrap();
+    let csv_path = fixtures::create_csv_with_content(dir.path(), "data.csv", "id\n1\n");
+
+    fixtures::exapump()
+        .args([
+            "upload",
+            csv_path.to_str().unwrap(),
+            "--table",
+            "my_table",
+            "--type",
+            "zip=VARCHAR(10)",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+            "--dry-run",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("column zip"));
+}
+
+#[test]
+fn csv_dry_run_reports_rows_that_decided_types() {
+    let dir = tempfile::tempdir().unwrap();
+    let csv_path = fixtures::create_csv_with_content(
+        dir.path(),
+        "data.csv",
+        "id,amount\n1,10\n2,20\n3,20.5\n",
+    );
+
+    fixtures::exapump()
+        .args([
+            "upload",
+            csv_path.to_str().unwrap(),
+            "--table",
+            "my_table",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+            "--dry-run",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Type inference (all rows):"))
+        .stdout(predicate::str::contains("amount: decimal from"))
+        .stdout(predicate::str::contains("data.csv row 3: \"20.5\""))
+        .stdout(predicate::str::contains("\"amount\" DOUBLE"));
+}
+
+#[test]
+fn csv_infer_rows_limits_the_sample() {
+    let dir = tempfile::tempdir().unwrap();
+    let csv_path = fixtures::create_csv_with_content(
+        dir.path(),
+        "data.csv",
+        "id,amount\n1,10\n2,20\n3,20.5\n",
+    );
+
+    fixtures::exapump()
+        .args([
+            "upload",
+            csv_path.to_str().unwrap(),
+            "--table",
+            "my_table",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+            "--infer-rows",
+            "2",
+            "--dry-run",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Type inference (first 2 rows of each file):",
+        ))
+        .stdout(predicate::str::contains("amount: integer from"))
+        .stdout(predicate::str::contains("\"amount\" DOUBLE").not());
+}
+
+#[test]
+fn csv_dry_run_applies_column_name_mode() {
+    let dir = tempfile::tempdir().unwrap();
+    let csv_path = fixtures::create_csv_with_content(
+        dir.path(),
+        "data.csv",
+        "customerId,Order Date,order_date\n1,2024-01-31,2024-02-01\n",
+    );
+
+    fixtures::exapump()
+        .args([
+            "upload",
+            csv_path.to_str().unwrap(),
+            "--table",
+            "my_table",
+            "--column-names",
+            "snake",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+            "--dry-run",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("customerId -> \"CUSTOMER_ID\""))
+        .stdout(predicate::str::contains("\"CUSTOMER_ID\" DECIMAL"))
+        .stdout(predicate::str::contains("\"ORDER_DATE\" DATE"))
+        .stdout(predicate::str::contains("\"ORDER_DATE_2\" DATE"));
+}
+
+#[tokio::test]
+async fn exasol_csv_upper_column_names_are_unquoted_identifiers() {
+    fixtures::require_exasol!();
+
+    let (mut conn, schema_name) = fixtures::setup_exasol_schema("EXAPUMP_CSV").await;
+
+    let table_name = format!("{schema_name}.UPPER_NAMES");
+    let dir = tempfile::tempdir().unwrap();
+    let csv_path = fixtures::create_csv_with_content(
+        dir.path(),
+        "data.csv",
+        "customerId,full name\n1,alice\n2,bob\n",
+    );
+
+    fixtures::exapump()
+        .timeout(std::time::Duration::from_secs(60))
+        .args([
+            "upload",
+            csv_path.to_str().unwrap(),
+            "--table",
+            &table_name,
+            "--column-names",
+            "upper",
+            "--dsn",
+            fixtures::DOCKER_DSN,
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Imported 2 rows"));
+
+    let rs = conn
+        .execute(&format!(
+            "SELECT full_name FROM {table_name} WHERE customerid = 2"
+        ))
+        .await
+        .unwrap();
+    let batches = rs.fetch_all().await.unwrap();
+    let name = arrow::util::display::array_value_to_string(batches[0].column(0), 0).unwrap();
+    assert_eq!(name, "bob");
+
+    let _ = conn
+        .execute_update(&format!("DROP SCHEMA {schema_name} CASCADE"))
+        .await;
+}
+
+#[test]
+fn csv_dry_run_infers_schema_from_compressed_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let first = fixtures::create_compressed_csv(dir.path(), "a.csv.gz", "id,amount\n1,10\n");
+    let second = fixtures::create_compressed_csv(dir.path(), "b.csv.zst", "id,amount\n2,2.5\n");
+
+    fixtures::exapump()
+        .args([
+            "upload",
+            first.to_str().unwrap(),
+            second.to_str().unwrap(),
+            "--table",
+            "my_table",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+            "--dry-run",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("b.csv.zst row 1: \"2.5\""))
+        .stdout(predicate::str::contains("\"amount\" DOUBLE"));
+}
+
+#[test]
+fn csv_compressed_parquet_is_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("data.parquet.gz");
+    std::fs::write(&path, b"").unwrap();
+
+    fixtures::exapump()
+        .args([
+            "upload",
+            path.to_str().unwrap(),
+            "--table",
+            "my_table",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+            "--dry-run",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("compressed Parquet"));
+}
+
+#[tokio::test]
+async fn exasol_csv_import_compressed_files() {
+    fixtures::require_exasol!();
+
+    let (mut conn, schema_name) = fixtures::setup_exasol_schema("EXAPUMP_CSV").await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let first =
+        fixtures::create_compressed_csv(dir.path(), "a.csv.gz", "id,name\n1,alice\n2,bob\n");
+    let second = fixtures::create_compressed_csv(
+        dir.path(),
+        "b.csv.zst",
+        "id,name\n3,carol\n4,dave\n5,eve\n",
+    );
+
+    let table_name = format!("{schema_name}.COMPRESSED");
+
+    fixtures::exapump()
+        .timeout(std::time::Duration::from_secs(60))
+        .args([
+            "upload",
+            first.to_str().unwrap(),
+            second.to_str().unwrap(),
+            "--table",
+            &table_name,
+            "--dsn",
+            fixtures::DOCKER_DSN,
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("a.csv.gz: 2 rows"))
+        .stdout(predicate::str::contains("Imported 5 rows from 2 files"))
+        .stdout(predicate::str::contains("bytes uncompressed"));
+
+    let rs = conn
+        .execute(&format!("SELECT * FROM {table_name}"))
+        .await
+        .unwrap();
+    let batches = rs.fetch_all().await.unwrap();
+    let row_count: usize = batches.iter().map(|b| b.num_rows()).sum();
+    assert_eq!(row_count, 5, "expected 5 rows in COMPRESSED");
+
+    let _ = conn
+        .execute_update(&format!("DROP SCHEMA {schema_name} CASCADE"))
+        .await;
+}
+
+#[test]
+fn csv_dry_run_with_max_errors_skips_malformed_rows() {
+    let dir = tempfile::tempdir().unwrap();
+    let csv_path = fixtures::create_csv_with_content(
+        dir.path(),
+        "ragged.csv",
+        "id,name\n1,alice\n2\n3,carol\n",
+    );
+
+    fixtures::exapump()
+        .args([
+            "upload",
+            csv_path.to_str().unwrap(),
+            "--table",
+            "my_table",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+            "--max-errors",
+            "10",
+            "--dry-run",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"id\": DECIMAL"))
+        .stdout(predicate::str::contains("\"name\": VARCHAR"));
+}
+
+#[test]
+fn csv_reject_file_requires_csv_input() {
+    let dir = tempfile::tempdir().unwrap();
+    let parquet_path = fixtures::create_test_parquet(dir.path());
+    let reject_path = dir.path().join("rejects.csv");
+
+    fixtures::exapump()
+        .args([
+            "upload",
+            parquet_path.to_str().unwrap(),
+            "--table",
+            "my_table",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+            "--reject-file",
+            reject_path.to_str().unwrap(),
+            "--dry-run",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("only apply to CSV input"));
+}
+
+#[tokio::test]
+async fn exasol_csv_import_writes_rejected_rows_to_reject_file() {
+    fixtures::require_exasol!();
+
+    let (mut conn, schema_name) = fixtures::setup_exasol_schema("EXAPUMP_CSV").await;
+    let table_name = format!("{schema_name}.REJECTS");
+
+    let dir = tempfile::tempdir().unwrap();
+    let csv_path = fixtures::create_csv_with_content(
+        dir.path(),
+        "orders.csv",
+        "id,name\n1,alice\nx,bob\n3\n4,dave\n",
+    );
+    let reject_path = dir.path().join("rejects.csv");
+
+    fixtures::exapump()
+        .timeout(std::time::Duration::from_secs(60))
+        .args([
+            "upload",
+            csv_path.to_str().unwrap(),
+            "--table",
+            &table_name,
+            "--dsn",
+            fixtures::DOCKER_DSN,
+            "--type",
+            "id=DECIMAL(18,0)",
+            "--reject-file",
+            reject_path.to_str().unwrap(),
+        ])
+        .assert()
+        .code(3)
+        .stdout(predicate::str::contains("Imported 2 rows"))
+        .stdout(predicate::str::contains("Rejected 2 rows"));
+
+    let rejects = std::fs::read_to_string(&reject_path).unwrap();
+    let lines: Vec<&str> = rejects.lines().collect();
+    assert_eq!(lines[0], "file,line,error,id,name");
+    assert!(lines[1].contains(",3,") && lines[1].contains("DECIMAL(18,0)"));
+    assert!(lines[2].contains(",4,") && lines[2].contains("expected 2 fields, found 1"));
+
+    let rs = conn
+        .execute(&format!("SELECT * FROM {table_name}"))
+        .await
+        .unwrap();
+    let batches = rs.fetch_all().await.unwrap();
+    let row_count: usize = batches.iter().map(|b| b.num_rows()).sum();
+    assert_eq!(row_count, 2, "expected 2 rows in REJECTS");
+
+    let _ = conn
+        .execute_update(&format!("DROP SCHEMA {schema_name} CASCADE"))
+        .await;
+}
+
+#[tokio::test]
+async fn exasol_csv_import_fails_beyond_max_errors() {
+    fixtures::require_exasol!();
+
+    let (mut conn, schema_name) = fixtures::setup_exasol_schema("EXAPUMP_CSV").await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let csv_path = fixtures::create_csv_with_content(
+        dir.path(),
+        "orders.csv",
+        "id,name\n1,alice\nx,bob\ny,carol\n",
+    );
+
+    fixtures::exapump()
+        .timeout(std::time::Duration::from_secs(60))
+        .args([
+            "upload",
+            csv_path.to_str().unwrap(),
+            "--table",
+            &format!("{schema_name}.TOO_MANY_REJECTS"),
+            "--dsn",
+            fixtures::DOCKER_DSN,
+            "--type",
+            "id=DECIMAL(18,0)",
+            "--max-errors",
+            "1",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Rejected"))
+        .stderr(predicate::str::contains("more than 1 rows rejected"));
+
+    let _ = conn
+        .execute_update(&format!("DROP SCHEMA {schema_name} CASCADE"))
+        .await;
+}
+
+#[test]
+fn csv_dry_run_directory_input() {
+    let dir = tempfile::tempdir().unwrap();
+    fixtures::create_csv_with_content(dir.path(), "a.csv", "id,name\n1,alice\n");
+    fixtures::create_csv_with_content(dir.path(), "b.csv", "id,name\n2,bob\n");
+    fixtures::create_csv_with_content(dir.path(), "_SUCCESS", "");
+
+    fixtures::exapump()
+        .args([
+            "upload",
+            dir.path().to_str().unwrap(),
+            "--table",
+            "my_table",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+            "--dry-run",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"name\""))
+        .stdout(predicate::str::contains("CREATE TABLE"));
+}
+
+#[test]
+fn csv_quoted_glob_without_matches_fails() {
+    let dir = tempfile::tempdir().unwrap();
+    let pattern = format!("{}/*.csv", dir.path().display());
+
+    fixtures::exapump()
+        .args([
+            "upload",
+            &pattern,
+            "--table",
+            "my_table",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+            "--dry-run",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("no files match pattern"));
+}
@@ -120,18 +120,17 @@ pub fn exapump() -> Command {
     cargo_bin_cmd!("exapump")
 }
 
-/// Creates a small Parquet file at `dir/test.parquet` with 3 columns and 3 rows.
-/// Returns the path to the created file.
+/// The 3 columns and 3 rows of the test Parquet and Arrow IPC files.
 #[allow(dead_code)]
-pub fn create_test_parquet(dir: &std::path::Path) -> PathBuf {
+fn test_batch() -> RecordBatch {
     let schema = Arc::new(Schema::new(vec![
         Field::new("id", DataType::Int64, false),
         Field::new("name", DataType::Utf8, true),
         Field::new("score", DataType::Float64, false),
     ]));
 
-    let batch = RecordBatch::try_new(
-        schema.clone(),
+    RecordBatch::try_new(
+        schema,
         vec![
             Arc::new(Int64Array::from(vec![1, 2, 3])),
             Arc::new(StringArray::from(vec![
@@ -142,16 +141,36 @@ pub fn create_test_parquet(dir: &std::path::Path) -> PathBuf {
             Arc::new(Float64Array::from(vec![95.5, 87.0, 92.3])),
         ],
     )
-    .unwrap();
+    .unwrap()
+}
 
+/// Creates a small Parquet file at `dir/test.parquet` with 3 columns and 3 rows.
+/// Returns the path to the created file.
+#[allow(dead_code)]
+pub fn create_test_parquet(dir: &std::path::Path) -> PathBuf {
+    let batch = test_batch();
     let path = dir.join("test.parquet");
     let file = std::fs::File::create(&path).unwrap();
-    let mut writer = ArrowWriter::try_new(file, schema, None).unwrap();
+    let mut writer = ArrowWriter::try_new(file, batch.schema(), None).unwrap();
     writer.write(&batch).unwrap();
     writer.close().unwrap();
     path
 }
 
+/// Creates an Arrow IPC file in the file format at `dir/{filename}` with the same 3
+/// columns and 3 rows as the test Parquet file.
+/// Returns the path to the created file.
+#[allow(dead_code)]
+pub fn create_test_arrow_ipc(dir: &std::path::Path, filename: &str) -> PathBuf {
+    let batch = test_batch();
+    let path = dir.join(filename);
+    let file = std::fs::File::create(&path).unwrap();
+    let mut writer = arrow::ipc::writer::FileWriter::try_new(file, &batch.schema()).unwrap();
+    writer.write(&batch).unwrap();
+    writer.finish().unwrap();
+    path
+}
+
 /// Creates a Parquet file with a SQL-reserved keyword column (`timestamp`).
 /// Returns the path to the created file.
 #[allow(dead_code)]
@@ -188,6 +207,53 @@ pub fn create_parquet_with_reserved_keyword(dir: &std::path::Path) -> PathBuf {
     path
 }
 
+/// Creates a Parquet file at `dir/nested.parquet` with an `id` column, a `customer`
+/// struct (`name`, `city`), and a `tags` list of strings, in 2 rows.
+/// Returns the path to the created file.
+#[allow(dead_code)]
+pub fn create_nested_parquet(dir: &std::path::Path) -> PathBuf {
+    use arrow::array::{Array, ArrayRef, ListBuilder, StringBuilder, StructArray};
+
+    let customer = StructArray::from(vec![
+        (
+            Arc::new(Field::new("name", DataType::Utf8, true)),
+            Arc::new(StringArray::from(vec!["Alice", "Bob"])) as ArrayRef,
+        ),
+        (
+            Arc::new(Field::new("city", DataType::Utf8, true)),
+            Arc::new(StringArray::from(vec![Some("Berlin"), None])) as ArrayRef,
+        ),
+    ]);
+    let mut tags = ListBuilder::new(StringBuilder::new());
+    tags.values().append_value("a");
+    tags.values().append_value("b");
+    tags.append(true);
+    tags.append(false);
+    let tags = tags.finish();
+
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Int64, false),
+        Field::new("customer", customer.data_type().clone(), true),
+        Field::new("tags", tags.data_type().clone(), true),
+    ]));
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(Int64Array::from(vec![1, 2])),
+            Arc::new(customer),
+            Arc::new(tags),
+        ],
+    )
+    .unwrap();
+
+    let path = dir.join("nested.parquet");
+    let file = std::fs::File::create(&path).unwrap();
+    let mut writer = ArrowWriter::try_new(file, schema, None).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+    path
+}
+
 /// Creates a small CSV file at `dir/test.csv` with 3 columns and 3 rows.
 /// Returns the path to the created file.
 #[allow(dead_code)]
@@ -209,3 +275,26 @@ pub fn create_csv_with_content(dir: &std::path::Path, filename: &str, content: &
     std::fs::write(&path, content).unwrap();
     path
 }
+
+/// Creates a compressed CSV file with custom content at `dir/{filename}`, using gzip
+/// for a `.gz` name and zstd for a `.zst` name.
+/// Returns the path to the created file.
+#[allow(dead_code)]
+pub fn create_compressed_csv(dir: &std::path::Path, filename: &str, content: &str) -> PathBuf {
+    use std::io::Write;
+
+    let path = dir.join(filename);
+    let file = std::fs::File::create(&path).unwrap();
+    if filename.ends_with(".gz") {
+        let mut encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+        encoder.write_all(content.as_bytes()).unwrap();
+        encoder.finish().unwrap();
+    } else if filename.ends_with(".zst") {
+        let mut encoder = zstd::stream::write::Encoder::new(file, 0).unwrap();
+        encoder.write_all(content.as_bytes()).unwrap();
+        encoder.finish().unwrap();
+    } else {
+        panic!("unsupported compression in {filename}");
+    }
+    path
+}
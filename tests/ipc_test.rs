@@ -0,0 +1,112 @@
+mod fixtures;
+
+use predicates::prelude::*;
+
+#[test]
+fn ipc_dry_run_shows_schema_from_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = fixtures::create_test_arrow_ipc(dir.path(), "test.feather");
+
+    fixtures::exapump()
+        .args([
+            "upload",
+            path.to_str().unwrap(),
+            "--table",
+            "test_schema.test_table",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+            "--dry-run",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("CREATE TABLE"))
+        .stdout(predicate::str::contains("\"id\" DECIMAL(36,0)"))
+        .stdout(predicate::str::contains("\"name\""))
+        .stdout(predicate::str::contains("\"score\" DOUBLE"));
+}
+
+#[test]
+fn ipc_compressed_file_is_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("test.arrow.gz");
+    std::fs::write(&path, b"").unwrap();
+
+    fixtures::exapump()
+        .args([
+            "upload",
+            path.to_str().unwrap(),
+            "--table",
+            "test_schema.test_table",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+            "--dry-run",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "compressed Arrow IPC files are not supported",
+        ));
+}
+
+#[test]
+fn ipc_invalid_file_fails() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("test.arrow");
+    std::fs::write(&path, "id,name\n1,a\n").unwrap();
+
+    fixtures::exapump()
+        .args([
+            "upload",
+            path.to_str().unwrap(),
+            "--table",
+            "test_schema.test_table",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+            "--dry-run",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("failed to read Arrow IPC file"));
+}
+
+#[tokio::test]
+async fn exasol_ipc_import_with_column_subset() {
+    fixtures::require_exasol!();
+
+    let (mut conn, schema_name) = fixtures::setup_exasol_schema("EXAPUMP_IPC").await;
+
+    let dir = tempfile::tempdir().unwrap();
+    fixtures::create_test_arrow_ipc(dir.path(), "a.arrow");
+    fixtures::create_test_arrow_ipc(dir.path(), "b.feather");
+    let table_name = format!("{schema_name}.IPC_IMPORT");
+
+    fixtures::exapump()
+        .timeout(std::time::Duration::from_secs(60))
+        .args([
+            "upload",
+            dir.path().to_str().unwrap(),
+            "--columns",
+            "name,id",
+            "--table",
+            &table_name,
+            "--dsn",
+            fixtures::DOCKER_DSN,
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Imported 6 rows from 2 files"));
+
+    let rs = conn
+        .execute(&format!(
+            "SELECT \"name\" FROM {table_name} WHERE \"id\" = 2"
+        ))
+        .await
+        .unwrap();
+    let batches = rs.fetch_all().await.unwrap();
+    let row_count: usize = batches.iter().map(|b| b.num_rows()).sum();
+    assert_eq!(row_count, 2, "expected both files to carry id 2");
+
+    let _ = conn
+        .execute_update(&format!("DROP SCHEMA {schema_name} CASCADE"))
+        .await;
+}
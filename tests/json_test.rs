@@ -0,0 +1,123 @@
+mod fixtures;
+
+use predicates::prelude::*;
+
+const RECORDS: &str = concat!(
+    r#"{"id": 1, "customer": {"name": "alice", "city": "Berlin"}, "tags": ["a", "b"]}"#,
+    "\n",
+    r#"{"id": 2, "customer": {"name": "bob"}}"#,
+    "\n",
+);
+
+#[test]
+fn json_dry_run_flattens_nested_objects() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("data.jsonl");
+    std::fs::write(&path, RECORDS).unwrap();
+
+    fixtures::exapump()
+        .args([
+            "upload",
+            path.to_str().unwrap(),
+            "--table",
+            "my_schema.events",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+            "--dry-run",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"id\" DECIMAL(36,0)"))
+        .stdout(predicate::str::contains(
+            "\"customer_name\" VARCHAR(2000000)",
+        ))
+        .stdout(predicate::str::contains(
+            "\"customer_city\" VARCHAR(2000000)",
+        ))
+        .stdout(predicate::str::contains("\"tags\" VARCHAR(2000000)"));
+}
+
+#[test]
+fn json_dry_run_keeps_nested_objects_as_json() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("data.ndjson");
+    std::fs::write(&path, RECORDS).unwrap();
+
+    fixtures::exapump()
+        .args([
+            "upload",
+            path.to_str().unwrap(),
+            "--table",
+            "my_schema.events",
+            "--nested",
+            "json",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+            "--dry-run",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"customer\" VARCHAR(2000000)"))
+        .stdout(predicate::str::contains("customer_name").not());
+}
+
+#[test]
+fn json_without_records_fails() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("data.jsonl");
+    std::fs::write(&path, "\n").unwrap();
+
+    fixtures::exapump()
+        .args([
+            "upload",
+            path.to_str().unwrap(),
+            "--table",
+            "my_schema.events",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+            "--dry-run",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("no records"));
+}
+
+#[tokio::test]
+async fn exasol_json_import_flattened_records() {
+    fixtures::require_exasol!();
+
+    let (mut conn, schema_name) = fixtures::setup_exasol_schema("EXAPUMP_JSON").await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("data.jsonl");
+    std::fs::write(&path, RECORDS).unwrap();
+    let table_name = format!("{schema_name}.EVENTS");
+
+    fixtures::exapump()
+        .timeout(std::time::Duration::from_secs(60))
+        .args([
+            "upload",
+            path.to_str().unwrap(),
+            "--table",
+            &table_name,
+            "--dsn",
+            fixtures::DOCKER_DSN,
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Imported 2 rows"));
+
+    let rs = conn
+        .execute(&format!(
+            "SELECT \"tags\" FROM {table_name} WHERE \"customer_name\" = 'alice'"
+        ))
+        .await
+        .unwrap();
+    let batches = rs.fetch_all().await.unwrap();
+    let tags = arrow::util::display::array_value_to_string(batches[0].column(0), 0).unwrap();
+    assert_eq!(tags, r#"["a","b"]"#);
+
+    let _ = conn
+        .execute_update(&format!("DROP SCHEMA {schema_name} CASCADE"))
+        .await;
+}
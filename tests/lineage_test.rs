@@ -0,0 +1,181 @@
+mod fixtures;
+
+use predicates::prelude::*;
+
+#[test]
+fn added_columns_are_appended_to_the_schema() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = fixtures::create_test_csv(dir.path());
+
+    fixtures::exapump()
+        .args([
+            "upload",
+            path.to_str().unwrap(),
+            "--table",
+            "my_schema.scores",
+            "--add-column",
+            "source_file=$FILE",
+            "--add-column",
+            "loaded_at=$NOW",
+            "--add-column",
+            "batch=2026-10",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+            "--dry-run",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"source_file\": VARCHAR(2000)"))
+        .stdout(predicate::str::contains("\"loaded_at\": TIMESTAMP"))
+        .stdout(predicate::str::contains("\"batch\": VARCHAR(7)"));
+}
+
+#[test]
+fn added_column_types_can_be_overridden() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = fixtures::create_test_csv(dir.path());
+
+    fixtures::exapump()
+        .args([
+            "upload",
+            path.to_str().unwrap(),
+            "--table",
+            "my_schema.scores",
+            "--add-column",
+            "batch=42",
+            "--type",
+            "batch=DECIMAL(10,0)",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+            "--dry-run",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"batch\": DECIMAL(10,0)"));
+}
+
+#[test]
+fn added_column_must_not_shadow_a_file_column() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = fixtures::create_test_csv(dir.path());
+
+    fixtures::exapump()
+        .args([
+            "upload",
+            path.to_str().unwrap(),
+            "--table",
+            "my_schema.scores",
+            "--add-column",
+            "name=$FILE",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+            "--dry-run",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "--add-column name is already a column of the table",
+        ));
+}
+
+#[tokio::test]
+async fn exasol_added_columns_are_filled_for_every_row() {
+    fixtures::require_exasol!();
+
+    let (mut conn, schema_name) = fixtures::setup_exasol_schema("EXAPUMP_LINEAGE").await;
+    let dir = tempfile::tempdir().unwrap();
+    let first = fixtures::create_csv_with_content(dir.path(), "a.csv", "id\n1\n2\n");
+    let second = fixtures::create_csv_with_content(dir.path(), "b.csv", "id\n3\n");
+    let table_name = format!("{schema_name}.EVENTS");
+
+    fixtures::exapump()
+        .timeout(std::time::Duration::from_secs(60))
+        .args([
+            "upload",
+            first.to_str().unwrap(),
+            second.to_str().unwrap(),
+            "--table",
+            &table_name,
+            "--column-names",
+            "upper",
+            "--add-column",
+            "source_file=$FILE",
+            "--add-column",
+            "loaded_at=$NOW",
+            "--dsn",
+            fixtures::DOCKER_DSN,
+        ])
+        .assert()
+        .success();
+
+    let rs = conn
+        .execute(&format!(
+            "SELECT SOURCE_FILE, COUNT(*), COUNT(DISTINCT LOADED_AT) FROM {table_name} \
+             GROUP BY SOURCE_FILE ORDER BY SOURCE_FILE"
+        ))
+        .await
+        .unwrap();
+    let batches = rs.fetch_all().await.unwrap();
+    let row_count: usize = batches.iter().map(|b| b.num_rows()).sum();
+    assert_eq!(row_count, 2, "expected one group per source file");
+    let batch = batches.iter().find(|b| b.num_rows() > 0).unwrap();
+    let value = |column, row| {
+        arrow::util::display::array_value_to_string(batch.column(column), row).unwrap()
+    };
+    assert!(value(0, 0).ends_with("a.csv"), "got: {}", value(0, 0));
+    assert_eq!(value(1, 0), "2");
+    assert!(value(0, 1).ends_with("b.csv"), "got: {}", value(0, 1));
+    assert_eq!(value(2, 1), "1");
+
+    let _ = conn
+        .execute_update(&format!("DROP SCHEMA {schema_name} CASCADE"))
+        .await;
+}
+
+#[tokio::test]
+async fn exasol_added_columns_fill_files_larger_than_one_batch() {
+    fixtures::require_exasol!();
+
+    let (mut conn, schema_name) = fixtures::setup_exasol_schema("EXAPUMP_LINEAGE").await;
+    let dir = tempfile::tempdir().unwrap();
+    let content: String = std::iter::once("id,name\n".to_string())
+        .chain((0..20000).map(|i| format!("{i},name {i}\n")))
+        .collect();
+    let csv_path = fixtures::create_csv_with_content(dir.path(), "big.csv", &content);
+    let table_name = format!("{schema_name}.EVENTS");
+
+    fixtures::exapump()
+        .timeout(std::time::Duration::from_secs(60))
+        .args([
+            "upload",
+            csv_path.to_str().unwrap(),
+            "--table",
+            &table_name,
+            "--column-names",
+            "upper",
+            "--add-column",
+            "batch=2026-10",
+            "--dsn",
+            fixtures::DOCKER_DSN,
+        ])
+        .assert()
+        .success();
+
+    let rs = conn
+        .execute(&format!(
+            "SELECT COUNT(*), COUNT(BATCH), MAX(ID) FROM {table_name}"
+        ))
+        .await
+        .unwrap();
+    let batches = rs.fetch_all().await.unwrap();
+    let batch = batches.iter().find(|b| b.num_rows() > 0).unwrap();
+    let value =
+        |column| arrow::util::display::array_value_to_string(batch.column(column), 0).unwrap();
+    assert_eq!(value(0), "20000");
+    assert_eq!(value(1), "20000");
+    assert_eq!(value(2), "19999");
+
+    let _ = conn
+        .execute_update(&format!("DROP SCHEMA {schema_name} CASCADE"))
+        .await;
+}
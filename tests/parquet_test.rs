@@ -227,3 +227,256 @@ async fn exasol_parquet_import_with_reserved_keyword_column() {
         .execute_update(&format!("DROP SCHEMA {schema_name} CASCADE"))
         .await;
 }
+
+#[test]
+fn dry_run_multiple_parquet_files_prints_one_schema() {
+    let first_dir = tempfile::tempdir().unwrap();
+    let second_dir = tempfile::tempdir().unwrap();
+    let first = fixtures::create_test_parquet(first_dir.path());
+    let second = fixtures::create_test_parquet(second_dir.path());
+
+    fixtures::exapump()
+        .args([
+            "upload",
+            first.to_str().unwrap(),
+            second.to_str().unwrap(),
+            "--table",
+            "test_schema.test_table",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+            "--dry-run",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"id\""))
+        .stdout(predicate::str::contains("CREATE TABLE").count(1));
+}
+
+#[test]
+fn multiple_parquet_files_with_mismatched_columns_fail() {
+    let dir = tempfile::tempdir().unwrap();
+    let first = fixtures::create_test_parquet(dir.path());
+    let second = fixtures::create_parquet_with_reserved_keyword(dir.path());
+
+    fixtures::exapump()
+        .args([
+            "upload",
+            first.to_str().unwrap(),
+            second.to_str().unwrap(),
+            "--table",
+            "test_schema.test_table",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("schema mismatch"))
+        .stderr(predicate::str::contains("reserved_keyword.parquet"));
+}
+
+#[test]
+fn dry_run_quoted_recursive_glob() {
+    let dir = tempfile::tempdir().unwrap();
+    let nested = dir.path().join("landing").join("dt=2026-10-01");
+    std::fs::create_dir_all(&nested).unwrap();
+    fixtures::create_test_parquet(&nested);
+
+    let pattern = format!("{}/landing/**/*.parquet", dir.path().display());
+
+    fixtures::exapump()
+        .args([
+            "upload",
+            &pattern,
+            "--table",
+            "test_schema.test_table",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+            "--dry-run",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"score\""))
+        .stdout(predicate::str::contains("CREATE TABLE"));
+}
+
+#[test]
+fn dry_run_directory_rejects_mixed_formats() {
+    let dir = tempfile::tempdir().unwrap();
+    fixtures::create_test_parquet(dir.path());
+    fixtures::create_csv_with_content(dir.path(), "extra.csv", "id\n1\n");
+
+    fixtures::exapump()
+        .args([
+            "upload",
+            dir.path().to_str().unwrap(),
+            "--table",
+            "test_schema.test_table",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+            "--dry-run",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot mix file formats"));
+}
+
+#[tokio::test]
+async fn exasol_parquet_parallel_import() {
+    fixtures::require_exasol!();
+
+    let (mut conn, schema_name) = fixtures::setup_exasol_schema("EXAPUMP_PQ").await;
+
+    let dir = tempfile::tempdir().unwrap();
+    for i in 0..4 {
+        let part_dir = dir.path().join(format!("part-{i}"));
+        std::fs::create_dir_all(&part_dir).unwrap();
+        fixtures::create_test_parquet(&part_dir);
+    }
+
+    let table_name = format!("{schema_name}.PARALLEL_IMPORT");
+
+    fixtures::exapump()
+        .timeout(std::time::Duration::from_secs(120))
+        .args([
+            "upload",
+            dir.path().to_str().unwrap(),
+            "--recursive",
+            "--parallel",
+            "3",
+            "--table",
+            &table_name,
+            "--dsn",
+            fixtures::DOCKER_DSN,
+        ])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("[4/4]"))
+        .stdout(predicate::str::contains("Imported 12 rows from 4 files"));
+
+    let rs = conn
+        .execute(&format!("SELECT * FROM {table_name}"))
+        .await
+        .unwrap();
+    let batches = rs.fetch_all().await.unwrap();
+    let row_count: usize = batches.iter().map(|b| b.num_rows()).sum();
+    assert_eq!(row_count, 12, "expected 12 rows in PARALLEL_IMPORT");
+
+    let _ = conn
+        .execute_update(&format!("DROP SCHEMA {schema_name} CASCADE"))
+        .await;
+}
+
+#[test]
+fn dry_run_flattens_nested_parquet_columns() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = fixtures::create_nested_parquet(dir.path());
+
+    fixtures::exapump()
+        .args([
+            "upload",
+            path.to_str().unwrap(),
+            "--table",
+            "test_schema.test_table",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+            "--dry-run",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Nested columns:"))
+        .stdout(predicate::str::contains(
+            "customer (struct) -> customer_name, customer_city",
+        ))
+        .stdout(predicate::str::contains("tags (list) -> tags as JSON"))
+        .stdout(predicate::str::contains(
+            "\"customer_name\" VARCHAR(2000000)",
+        ))
+        .stdout(predicate::str::contains("\"tags\" VARCHAR(2000000)"));
+}
+
+#[test]
+fn dry_run_keeps_nested_parquet_columns_as_json() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = fixtures::create_nested_parquet(dir.path());
+
+    fixtures::exapump()
+        .args([
+            "upload",
+            path.to_str().unwrap(),
+            "--table",
+            "test_schema.test_table",
+            "--nested",
+            "json",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+            "--dry-run",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "customer (struct) -> customer as JSON",
+        ))
+        .stdout(predicate::str::contains("\"customer\" VARCHAR(2000000)"))
+        .stdout(predicate::str::contains("customer_name").not());
+}
+
+#[test]
+fn nested_parquet_columns_can_be_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = fixtures::create_nested_parquet(dir.path());
+
+    fixtures::exapump()
+        .args([
+            "upload",
+            path.to_str().unwrap(),
+            "--table",
+            "test_schema.test_table",
+            "--nested",
+            "reject",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+            "--dry-run",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("customer (struct), tags (list)"));
+}
+
+#[tokio::test]
+async fn exasol_nested_parquet_import() {
+    fixtures::require_exasol!();
+
+    let (mut conn, schema_name) = fixtures::setup_exasol_schema("EXAPUMP_PQ").await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = fixtures::create_nested_parquet(dir.path());
+    let table_name = format!("{schema_name}.NESTED");
+
+    fixtures::exapump()
+        .timeout(std::time::Duration::from_secs(60))
+        .args([
+            "upload",
+            path.to_str().unwrap(),
+            "--table",
+            &table_name,
+            "--dsn",
+            fixtures::DOCKER_DSN,
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Imported 2 rows"));
+
+    let rs = conn
+        .execute(&format!(
+            "SELECT \"customer_city\", \"tags\" FROM {table_name} ORDER BY \"id\""
+        ))
+        .await
+        .unwrap();
+    let batches = rs.fetch_all().await.unwrap();
+    let row_count: usize = batches.iter().map(|b| b.num_rows()).sum();
+    assert_eq!(row_count, 2, "expected 2 rows in NESTED");
+
+    let _ = conn
+        .execute_update(&format!("DROP SCHEMA {schema_name} CASCADE"))
+        .await;
+}
@@ -0,0 +1,126 @@
+mod fixtures;
+
+use predicates::prelude::*;
+use std::path::PathBuf;
+
+/// Creates `events/dt=.../region=.../test.parquet` under `dir` for each partition.
+fn create_partitioned_parquet(dir: &std::path::Path, partitions: &[(&str, &str)]) -> PathBuf {
+    let events = dir.join("events");
+    for (dt, region) in partitions {
+        let partition = events
+            .join(format!("dt={dt}"))
+            .join(format!("region={region}"));
+        std::fs::create_dir_all(&partition).unwrap();
+        fixtures::create_test_parquet(&partition);
+    }
+    events
+}
+
+#[test]
+fn partition_directories_become_typed_columns() {
+    let dir = tempfile::tempdir().unwrap();
+    let events =
+        create_partitioned_parquet(dir.path(), &[("2026-10-01", "eu"), ("2026-10-02", "us")]);
+
+    fixtures::exapump()
+        .args([
+            "upload",
+            events.to_str().unwrap(),
+            "--recursive",
+            "--table",
+            "my_schema.events",
+            "--partition-columns",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+            "--dry-run",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"dt\": DATE"))
+        .stdout(predicate::str::contains("\"region\": VARCHAR"));
+}
+
+#[test]
+fn partition_columns_need_key_value_directories() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = fixtures::create_test_parquet(dir.path());
+
+    fixtures::exapump()
+        .args([
+            "upload",
+            path.to_str().unwrap(),
+            "--table",
+            "my_schema.events",
+            "--partition-columns",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+            "--dry-run",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("no key=value directories"));
+}
+
+#[test]
+fn partition_columns_refuse_stdin() {
+    fixtures::exapump()
+        .args([
+            "upload",
+            "-",
+            "--format",
+            "csv",
+            "--table",
+            "my_schema.events",
+            "--partition-columns",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+        ])
+        .write_stdin("id\n1\n")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "--partition-columns reads the file paths",
+        ));
+}
+
+#[tokio::test]
+async fn exasol_partition_columns_are_filled_per_file() {
+    fixtures::require_exasol!();
+
+    let (mut conn, schema_name) = fixtures::setup_exasol_schema("EXAPUMP_PARTITION").await;
+    let dir = tempfile::tempdir().unwrap();
+    let events =
+        create_partitioned_parquet(dir.path(), &[("2026-10-01", "eu"), ("2026-10-02", "us")]);
+    let table_name = format!("{schema_name}.EVENTS");
+
+    fixtures::exapump()
+        .timeout(std::time::Duration::from_secs(60))
+        .args([
+            "upload",
+            events.to_str().unwrap(),
+            "--recursive",
+            "--table",
+            &table_name,
+            "--column-names",
+            "upper",
+            "--partition-columns",
+            "--dsn",
+            fixtures::DOCKER_DSN,
+        ])
+        .assert()
+        .success();
+
+    let rs = conn
+        .execute(&format!(
+            "SELECT COUNT(*) FROM {table_name} WHERE DT = DATE '2026-10-02' AND REGION = 'us'"
+        ))
+        .await
+        .unwrap();
+    let batches = rs.fetch_all().await.unwrap();
+    let count = arrow::util::display::array_value_to_string(batches[0].column(0), 0).unwrap();
+    assert_eq!(count, "3", "expected the 3 rows of the us partition");
+
+    let _ = conn
+        .execute_update(&format!("DROP SCHEMA {schema_name} CASCADE"))
+        .await;
+}
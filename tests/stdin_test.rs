@@ -0,0 +1,174 @@
+mod fixtures;
+
+use predicates::prelude::*;
+
+#[test]
+fn stdin_csv_dry_run_shows_inferred_schema() {
+    fixtures::exapump()
+        .args([
+            "upload",
+            "-",
+            "--format",
+            "csv",
+            "--table",
+            "my_schema.events",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+            "--dry-run",
+        ])
+        .write_stdin("id,name\n1,alice\n2,bob\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"id\" DECIMAL(36,0)"))
+        .stdout(predicate::str::contains("\"name\" VARCHAR(2000000)"));
+}
+
+#[test]
+fn stdin_json_dry_run_shows_inferred_schema() {
+    fixtures::exapump()
+        .args([
+            "upload",
+            "-",
+            "--format",
+            "json",
+            "--table",
+            "my_schema.events",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+            "--dry-run",
+        ])
+        .write_stdin("{\"id\": 1, \"customer\": {\"name\": \"alice\"}}\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "\"customer_name\" VARCHAR(2000000)",
+        ));
+}
+
+#[test]
+fn stdin_infers_types_from_the_sample_only() {
+    fixtures::exapump()
+        .args([
+            "upload",
+            "-",
+            "--format",
+            "csv",
+            "--infer-rows",
+            "2",
+            "--table",
+            "my_schema.events",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+            "--dry-run",
+        ])
+        .write_stdin("amount\n1\n2\n2.5\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"amount\" DECIMAL(36,0)"));
+}
+
+#[test]
+fn stdin_requires_format() {
+    fixtures::exapump()
+        .args([
+            "upload",
+            "-",
+            "--table",
+            "my_schema.events",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+            "--dry-run",
+        ])
+        .write_stdin("id\n1\n")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("requires --format"));
+}
+
+#[test]
+fn stdin_cannot_be_combined_with_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let csv_path = fixtures::create_test_csv(dir.path());
+
+    fixtures::exapump()
+        .args([
+            "upload",
+            "-",
+            csv_path.to_str().unwrap(),
+            "--format",
+            "csv",
+            "--table",
+            "my_schema.events",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+            "--dry-run",
+        ])
+        .write_stdin("id\n1\n")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be combined"));
+}
+
+#[test]
+fn format_requires_stdin() {
+    let dir = tempfile::tempdir().unwrap();
+    let csv_path = fixtures::create_test_csv(dir.path());
+
+    fixtures::exapump()
+        .args([
+            "upload",
+            csv_path.to_str().unwrap(),
+            "--format",
+            "csv",
+            "--table",
+            "my_schema.events",
+            "--dsn",
+            fixtures::DUMMY_DSN,
+            "--dry-run",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--format only applies to stdin"));
+}
+
+#[tokio::test]
+async fn exasol_stdin_import_beyond_the_sample() {
+    fixtures::require_exasol!();
+
+    let (mut conn, schema_name) = fixtures::setup_exasol_schema("EXAPUMP_STDIN").await;
+    let table_name = format!("{schema_name}.STDIN_IMPORT");
+
+    let mut content = String::from("id,name\n");
+    for i in 0..25_000 {
+        content.push_str(&format!("{i},name {i}\n"));
+    }
+
+    fixtures::exapump()
+        .timeout(std::time::Duration::from_secs(60))
+        .args([
+            "upload",
+            "-",
+            "--format",
+            "csv",
+            "--table",
+            &table_name,
+            "--dsn",
+            fixtures::DOCKER_DSN,
+        ])
+        .write_stdin(content)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Imported 25000 rows"));
+
+    let rs = conn
+        .execute(&format!("SELECT * FROM {table_name}"))
+        .await
+        .unwrap();
+    let batches = rs.fetch_all().await.unwrap();
+    let row_count: usize = batches.iter().map(|b| b.num_rows()).sum();
+    assert_eq!(row_count, 25_000, "expected 25000 rows in STDIN_IMPORT");
+
+    let _ = conn
+        .execute_update(&format!("DROP SCHEMA {schema_name} CASCADE"))
+        .await;
+}